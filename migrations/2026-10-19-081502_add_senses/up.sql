@@ -0,0 +1,7 @@
+CREATE TABLE senses (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    phrase_id INTEGER NOT NULL REFERENCES phrases(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    translation TEXT NOT NULL,
+    note TEXT NOT NULL
+);
@@ -0,0 +1,8 @@
+CREATE TABLE examples (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    phrase_id INTEGER NOT NULL REFERENCES phrases(id) ON DELETE CASCADE,
+    hanzi TEXT NOT NULL,
+    pinyin TEXT NOT NULL,
+    translation TEXT NOT NULL,
+    UNIQUE (phrase_id, hanzi)
+);
@@ -0,0 +1,48 @@
+CREATE TABLE phrases_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    original TEXT NOT NULL UNIQUE,
+    pinyin TEXT NOT NULL,
+    translation TEXT NOT NULL
+);
+
+INSERT INTO phrases_new (id, original, pinyin, translation)
+SELECT id, original, pinyin, COALESCE(
+    (SELECT translation FROM translations WHERE phrase_id = phrases.id AND language = 'English'),
+    (SELECT translation FROM translations WHERE phrase_id = phrases.id ORDER BY id LIMIT 1),
+    ''
+)
+FROM phrases;
+
+DROP TABLE phrases;
+ALTER TABLE phrases_new RENAME TO phrases;
+
+CREATE TABLE senses_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    phrase_id INTEGER NOT NULL REFERENCES phrases(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    translation TEXT NOT NULL,
+    note TEXT NOT NULL
+);
+
+INSERT INTO senses_new (id, phrase_id, position, translation, note)
+SELECT id, phrase_id, position, translation, note FROM senses;
+
+DROP TABLE senses;
+ALTER TABLE senses_new RENAME TO senses;
+
+CREATE TABLE examples_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    phrase_id INTEGER NOT NULL REFERENCES phrases(id) ON DELETE CASCADE,
+    hanzi TEXT NOT NULL,
+    pinyin TEXT NOT NULL,
+    translation TEXT NOT NULL,
+    UNIQUE (phrase_id, hanzi)
+);
+
+INSERT INTO examples_new (id, phrase_id, hanzi, pinyin, translation)
+SELECT id, phrase_id, hanzi, pinyin, translation FROM examples;
+
+DROP TABLE examples;
+ALTER TABLE examples_new RENAME TO examples;
+
+DROP TABLE translations;
@@ -0,0 +1,26 @@
+CREATE TABLE translations (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    phrase_id INTEGER NOT NULL REFERENCES phrases(id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    translation TEXT NOT NULL,
+    UNIQUE (phrase_id, language)
+);
+
+INSERT INTO translations (phrase_id, language, translation)
+SELECT id, 'English', translation FROM phrases;
+
+CREATE TABLE phrases_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    original TEXT NOT NULL UNIQUE,
+    pinyin TEXT NOT NULL
+);
+
+INSERT INTO phrases_new (id, original, pinyin)
+SELECT id, original, pinyin FROM phrases;
+
+DROP TABLE phrases;
+ALTER TABLE phrases_new RENAME TO phrases;
+
+ALTER TABLE senses ADD COLUMN language TEXT NOT NULL DEFAULT 'English';
+
+ALTER TABLE examples ADD COLUMN language TEXT NOT NULL DEFAULT 'English';
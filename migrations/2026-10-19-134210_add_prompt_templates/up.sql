@@ -0,0 +1,5 @@
+CREATE TABLE prompt_templates (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    template TEXT NOT NULL
+);
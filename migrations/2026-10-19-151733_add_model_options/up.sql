@@ -0,0 +1,9 @@
+CREATE TABLE model_options (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    model TEXT NOT NULL UNIQUE,
+    temperature REAL,
+    top_p REAL,
+    num_ctx INTEGER,
+    seed INTEGER,
+    system_prompt TEXT NOT NULL DEFAULT ''
+);
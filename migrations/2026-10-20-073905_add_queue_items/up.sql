@@ -0,0 +1,8 @@
+CREATE TABLE queue_items (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    text TEXT NOT NULL,
+    language TEXT NOT NULL,
+    status TEXT NOT NULL,
+    result TEXT,
+    error TEXT
+);
@@ -0,0 +1,10 @@
+CREATE TABLE lookups (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    input TEXT NOT NULL,
+    model TEXT NOT NULL,
+    language TEXT NOT NULL,
+    result TEXT,
+    error TEXT,
+    duration_ms INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
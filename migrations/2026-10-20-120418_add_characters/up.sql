@@ -0,0 +1,11 @@
+CREATE TABLE characters (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    character TEXT NOT NULL UNIQUE,
+    definition TEXT NOT NULL DEFAULT '',
+    pinyin TEXT NOT NULL DEFAULT '',
+    decomposition TEXT NOT NULL DEFAULT '',
+    radical TEXT NOT NULL DEFAULT '',
+    stroke_count INTEGER
+);
+
+CREATE INDEX characters_radical ON characters (radical);
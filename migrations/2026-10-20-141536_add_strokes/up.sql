@@ -0,0 +1,8 @@
+CREATE TABLE strokes (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    character TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    median TEXT NOT NULL,
+    UNIQUE (character, position)
+);
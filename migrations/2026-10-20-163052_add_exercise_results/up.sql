@@ -0,0 +1,10 @@
+CREATE TABLE exercise_results (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    exercise TEXT NOT NULL,
+    item TEXT NOT NULL,
+    correct BOOLEAN NOT NULL,
+    mistakes INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE INDEX exercise_results_item ON exercise_results (exercise, item);
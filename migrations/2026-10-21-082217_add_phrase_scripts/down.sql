@@ -0,0 +1,13 @@
+DROP INDEX phrases_simplified;
+
+CREATE TABLE phrases_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    original TEXT NOT NULL UNIQUE,
+    pinyin TEXT NOT NULL
+);
+
+INSERT INTO phrases_new (id, original, pinyin)
+SELECT id, original, pinyin FROM phrases;
+
+DROP TABLE phrases;
+ALTER TABLE phrases_new RENAME TO phrases;
@@ -0,0 +1,4 @@
+ALTER TABLE phrases ADD COLUMN simplified TEXT NOT NULL DEFAULT '';
+ALTER TABLE phrases ADD COLUMN traditional TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX phrases_simplified ON phrases (simplified);
@@ -0,0 +1,17 @@
+CREATE TABLE phrases_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    original TEXT NOT NULL UNIQUE,
+    pinyin TEXT NOT NULL,
+    simplified TEXT NOT NULL DEFAULT '',
+    traditional TEXT NOT NULL DEFAULT ''
+);
+
+INSERT INTO phrases_new (id, original, pinyin, simplified, traditional)
+SELECT id, original, pinyin, simplified, traditional FROM phrases;
+
+DROP TABLE phrases;
+ALTER TABLE phrases_new RENAME TO phrases;
+
+CREATE INDEX phrases_simplified ON phrases (simplified);
+
+DROP TABLE hsk_words;
@@ -0,0 +1,10 @@
+CREATE TABLE hsk_words (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    word TEXT NOT NULL,
+    version TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    UNIQUE (word, version)
+);
+
+ALTER TABLE phrases ADD COLUMN hsk2_level INTEGER;
+ALTER TABLE phrases ADD COLUMN hsk3_level INTEGER;
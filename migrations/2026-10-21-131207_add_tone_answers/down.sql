@@ -0,0 +1 @@
+DROP TABLE tone_answers;
@@ -0,0 +1,11 @@
+CREATE TABLE tone_answers (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    item TEXT NOT NULL,
+    expected INTEGER NOT NULL,
+    answered INTEGER,
+    previous_expected INTEGER,
+    previous_answered INTEGER,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
+
+CREATE INDEX tone_answers_expected ON tone_answers (expected);
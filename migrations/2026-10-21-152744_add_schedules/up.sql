@@ -0,0 +1,10 @@
+CREATE TABLE schedules (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    item TEXT NOT NULL,
+    exercise TEXT NOT NULL,
+    repetitions INTEGER NOT NULL DEFAULT 0,
+    interval_days INTEGER NOT NULL DEFAULT 0,
+    ease REAL NOT NULL DEFAULT 2.5,
+    reviewed_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (item, exercise)
+);
@@ -0,0 +1,14 @@
+CREATE TABLE events_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    item TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime'))
+);
+
+INSERT INTO events_new (id, kind, item, created_at)
+SELECT id, kind, item, created_at FROM events;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+
+CREATE INDEX events_kind ON events (kind, created_at);
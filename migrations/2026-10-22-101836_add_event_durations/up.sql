@@ -0,0 +1 @@
+ALTER TABLE events ADD COLUMN duration_ms INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,20 @@
+CREATE TABLE phrases_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    original TEXT NOT NULL UNIQUE,
+    pinyin TEXT NOT NULL,
+    simplified TEXT NOT NULL DEFAULT '',
+    traditional TEXT NOT NULL DEFAULT '',
+    hsk2_level INTEGER,
+    hsk3_level INTEGER
+);
+
+INSERT INTO phrases_new (id, original, pinyin, simplified, traditional, hsk2_level, hsk3_level)
+SELECT id, original, pinyin, simplified, traditional, hsk2_level, hsk3_level FROM phrases;
+
+DROP TABLE phrases;
+ALTER TABLE phrases_new RENAME TO phrases;
+
+CREATE INDEX phrases_simplified ON phrases (simplified);
+
+DROP TABLE document_lines;
+DROP TABLE documents;
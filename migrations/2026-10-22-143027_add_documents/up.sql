@@ -0,0 +1,19 @@
+CREATE TABLE documents (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL,
+    path TEXT NOT NULL,
+    format TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (path)
+);
+
+CREATE TABLE document_lines (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    reference TEXT NOT NULL,
+    text TEXT NOT NULL,
+    UNIQUE (document_id, position)
+);
+
+ALTER TABLE phrases ADD COLUMN document_line_id INTEGER REFERENCES document_lines(id) ON DELETE SET NULL;
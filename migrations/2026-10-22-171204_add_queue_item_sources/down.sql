@@ -0,0 +1,14 @@
+CREATE TABLE queue_items_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    text TEXT NOT NULL,
+    language TEXT NOT NULL,
+    status TEXT NOT NULL,
+    result TEXT,
+    error TEXT
+);
+
+INSERT INTO queue_items_new (id, text, language, status, result, error)
+SELECT id, text, language, status, result, error FROM queue_items;
+
+DROP TABLE queue_items;
+ALTER TABLE queue_items_new RENAME TO queue_items;
@@ -0,0 +1 @@
+ALTER TABLE queue_items ADD COLUMN document_line_id INTEGER REFERENCES document_lines(id) ON DELETE SET NULL;
@@ -0,0 +1,15 @@
+CREATE TABLE queue_items_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    text TEXT NOT NULL,
+    language TEXT NOT NULL,
+    status TEXT NOT NULL,
+    result TEXT,
+    error TEXT,
+    document_line_id INTEGER REFERENCES document_lines(id) ON DELETE SET NULL
+);
+
+INSERT INTO queue_items_new (id, text, language, status, result, error, document_line_id)
+SELECT id, text, language, status, result, error, document_line_id FROM queue_items;
+
+DROP TABLE queue_items;
+ALTER TABLE queue_items_new RENAME TO queue_items;
@@ -0,0 +1,2 @@
+ALTER TABLE queue_items ADD COLUMN direction TEXT NOT NULL DEFAULT 'detect';
+ALTER TABLE queue_items ADD COLUMN llm_model TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,14 @@
+CREATE TABLE documents_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL,
+    path TEXT NOT NULL,
+    format TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    UNIQUE (path)
+);
+
+INSERT INTO documents_new (id, title, path, format, created_at)
+SELECT id, title, path, format, created_at FROM documents;
+
+DROP TABLE documents;
+ALTER TABLE documents_new RENAME TO documents;
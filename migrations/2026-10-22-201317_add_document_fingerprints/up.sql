@@ -0,0 +1 @@
+ALTER TABLE documents ADD COLUMN fingerprint TEXT NOT NULL DEFAULT '';
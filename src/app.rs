@@ -6,26 +6,281 @@ use egui_modal_spinner::ModalSpinner;
 use egui_notify::Anchor;
 use egui_notify::Toasts;
 use poll_promise::Promise;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
+use crate::characters;
+use crate::cjk;
+use crate::clipboard;
+use crate::dictionary;
+use crate::documents;
+use crate::drill;
+use crate::exercises;
+use crate::goals;
+use crate::handwriting;
+use crate::hsk;
 use crate::llm;
 use crate::model;
 use crate::persistence;
+use crate::pinyin;
+use crate::reader;
+use crate::script;
+use crate::settings;
+use crate::statistics;
+use crate::tts;
+
+const MAX_COMPARISON_MODELS: usize = 2;
+const HISTORY_LIMIT: i64 = 100;
+const STROKE_DURATION: f32 = 0.8;
+const PRACTICE_SIZE: usize = 10;
+
+pub(crate) struct TrackedQuery {
+    pub(crate) input: String,
+    pub(crate) llm_model: String,
+    pub(crate) promise: Promise<Result<model::Phrase, llm::LLMError>>,
+    pub(crate) retry: llm::Retry,
+    pub(crate) start: Instant,
+}
+
+pub(crate) struct Candidate {
+    pub(crate) llm_model: String,
+    pub(crate) result: Result<model::Phrase, String>,
+    pub(crate) duration: Duration,
+}
+
+pub(crate) struct QueueJob {
+    pub(crate) id: i32,
+    pub(crate) llm_model: String,
+    pub(crate) promise: Promise<Result<model::Phrase, llm::LLMError>>,
+    pub(crate) start: Instant,
+}
+
+#[derive(Default)]
+pub(crate) struct StrokeAnimation {
+    pub(crate) step: usize,
+    pub(crate) progress: f32,
+    pub(crate) playing: bool,
+}
+
+impl StrokeAnimation {
+    pub(crate) fn replay(&mut self) {
+        self.step = 0;
+        self.progress = 0.;
+        self.playing = true;
+    }
+
+    pub(crate) fn toggle(&mut self, stroke_count: usize) {
+        if self.step >= stroke_count {
+            self.replay();
+        } else {
+            self.playing = !self.playing;
+        }
+    }
+
+    pub(crate) fn forward(&mut self, stroke_count: usize) {
+        self.playing = false;
+        self.progress = 0.;
+        self.step = (self.step + 1).min(stroke_count);
+    }
+
+    pub(crate) fn back(&mut self) {
+        self.playing = false;
+        self.progress = 0.;
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub(crate) fn advance(&mut self, seconds: f32, stroke_count: usize) {
+        if !self.playing {
+            return;
+        }
+        self.progress += seconds / STROKE_DURATION;
+        if self.progress >= 1. {
+            self.progress = 0.;
+            self.step += 1;
+        }
+        if self.step >= stroke_count {
+            self.step = stroke_count;
+            self.playing = false;
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Practice {
+    pub(crate) characters: Vec<model::Character>,
+    pub(crate) index: usize,
+    pub(crate) step: usize,
+    pub(crate) mistakes: u32,
+    pub(crate) drawing: Vec<[f32; 2]>,
+    pub(crate) feedback: Option<model::StrokeFeedback>,
+    pub(crate) hint: bool,
+}
+
+impl Practice {
+    pub(crate) fn character(&self) -> Option<&model::Character> {
+        self.characters.get(self.index)
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.character()
+            .is_some_and(|character| self.step >= character.strokes.len())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Quiz {
+    pub(crate) kind: model::ExerciseKind,
+    pub(crate) cards: Vec<model::Card>,
+    pub(crate) index: usize,
+    pub(crate) answer: String,
+    pub(crate) revealed: bool,
+    pub(crate) result: Option<bool>,
+    pub(crate) correct: usize,
+}
+
+impl Quiz {
+    pub(crate) fn card(&self) -> Option<&model::Card> {
+        self.cards.get(self.index)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Reader {
+    pub(crate) text: String,
+    pub(crate) path: String,
+    pub(crate) documents: Vec<model::Document>,
+    pub(crate) document: Option<model::Document>,
+    pub(crate) lines: Vec<model::DocumentLine>,
+    pub(crate) sentences: Vec<String>,
+    pub(crate) segments: Vec<model::Segment>,
+    pub(crate) known_words: HashSet<String>,
+    pub(crate) selected: Option<usize>,
+    pub(crate) scroll_to_selection: bool,
+    pub(crate) gloss: Option<model::Phrase>,
+    pub(crate) gloss_query: Option<TrackedQuery>,
+}
+
+impl Reader {
+    pub(crate) fn word(&self) -> Option<&str> {
+        self.selected
+            .and_then(|index| self.segments.get(index))
+            .map(|segment| segment.text.as_str())
+    }
+
+    pub(crate) fn sentence(&self) -> Option<&str> {
+        self.selected
+            .and_then(|index| self.segments.get(index))
+            .and_then(|segment| self.sentences.get(segment.sentence))
+            .map(|sentence| sentence.trim())
+    }
+
+    pub(crate) fn source_line(&self) -> Option<&model::DocumentLine> {
+        self.document.as_ref()?;
+        self.selected
+            .and_then(|index| self.segments.get(index))
+            .and_then(|segment| self.lines.get(segment.line))
+    }
+
+    pub(crate) fn is_known(&self, word: &str) -> bool {
+        self.known_words.contains(word)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ToneDrill {
+    pub(crate) phrases: Vec<model::Phrase>,
+    pub(crate) index: usize,
+    pub(crate) answer: String,
+    pub(crate) checks: Vec<model::SyllableCheck>,
+    pub(crate) statistics: model::ToneStatistics,
+}
+
+impl ToneDrill {
+    pub(crate) fn phrase(&self) -> Option<&model::Phrase> {
+        self.phrases.get(self.index)
+    }
+
+    pub(crate) fn is_checked(&self) -> bool {
+        !self.checks.is_empty()
+    }
+}
 
 pub(crate) struct HanziApp {
     pub(crate) local_llm_models: Vec<String>,
     pub(crate) selected_llm_model: String,
+    pub(crate) generation_options: model::GenerationOptions,
+    pub(crate) target_language: String,
+    pub(crate) preferred_script: model::Script,
+    pub(crate) converter: script::Converter,
+    pub(crate) hsk_index: hsk::Index,
+    pub(crate) dictionary: dictionary::Dictionary,
+    pub(crate) hsk_version: model::HskVersion,
+    pub(crate) hsk_filter: Option<i32>,
+    pub(crate) hsk_coverage: Vec<(i32, i64, i64)>,
+    pub(crate) tts_settings: model::TtsSettings,
+    pub(crate) tone_colors: model::ToneColors,
+    pub(crate) pinyin_layout: model::PinyinLayout,
+    pub(crate) speech: Arc<dyn tts::SpeechBackend>,
+    pub(crate) speech_playback: Option<Promise<Result<(), tts::TtsError>>>,
+    pub(crate) prompt_templates: model::PromptTemplates,
+    pub(crate) selected_prompt_kind: model::PromptKind,
+    pub(crate) prompt_template_input: String,
+    pub(crate) template_validation: Option<Promise<Result<(), llm::LLMError>>>,
     pub(crate) database_url: String,
     pub(crate) toasts: Toasts,
     pub(crate) spinner: ModalSpinner,
     pub(crate) phrase_input: String,
+    pub(crate) direction: model::Direction,
     pub(crate) translation_input: String,
     pub(crate) pinyin_input: String,
-    pub(crate) llm_query: Option<Promise<Result<model::Phrase, llm::LLMError>>>,
-    pub(crate) llm_query_start: Option<Instant>,
+    pub(crate) senses_input: Vec<model::Sense>,
+    pub(crate) llm_queries: Vec<TrackedQuery>,
+    pub(crate) comparison_models: Vec<String>,
+    pub(crate) comparing: bool,
+    pub(crate) max_attempts: u32,
+    pub(crate) candidates: Vec<Candidate>,
+    pub(crate) examples_query: Option<Promise<Result<Vec<model::Example>, llm::LLMError>>>,
+    pub(crate) examples_query_start: Option<Instant>,
+    pub(crate) examples_retry: llm::Retry,
+    pub(crate) examples_count: usize,
+    pub(crate) candidate_examples: Vec<model::Example>,
+    pub(crate) character: Option<model::Character>,
+    pub(crate) stroke_animation: StrokeAnimation,
+    pub(crate) practice: Practice,
+    pub(crate) tone_drill: ToneDrill,
+    pub(crate) quiz: Quiz,
+    pub(crate) reader: Reader,
+    pub(crate) statistics: model::Statistics,
+    pub(crate) daily_goals: model::DailyGoals,
+    pub(crate) goal_progress: model::GoalProgress,
+    pub(crate) study_start: Option<(Instant, model::ExerciseKind)>,
+    pub(crate) clipboard_settings: model::ClipboardSettings,
+    pub(crate) clipboard_watcher: Option<clipboard::Watcher>,
+    pub(crate) clipboard_pending: Option<String>,
+    pub(crate) clipboard_lookup_at: Option<Instant>,
+    pub(crate) history: Vec<model::Lookup>,
+    pub(crate) queue: Vec<model::QueueItem>,
+    pub(crate) queue_job: Option<QueueJob>,
+    pub(crate) queue_selection: HashSet<i32>,
+    pub(crate) batch_input: String,
+    pub(crate) batch_sources: HashMap<String, i32>,
     pub(crate) phrase: Option<model::Phrase>,
     pub(crate) phrases: Vec<model::Phrase>,
+    pub(crate) open_examples: bool,
+    pub(crate) open_batch: bool,
+    pub(crate) open_character: bool,
+    pub(crate) open_history: bool,
+    pub(crate) open_hsk_coverage: bool,
+    pub(crate) open_exercises: bool,
+    pub(crate) open_practice: bool,
+    pub(crate) open_quiz: bool,
+    pub(crate) open_reader: bool,
+    pub(crate) open_statistics: bool,
+    pub(crate) open_tone_drill: bool,
+    pub(crate) open_queue: bool,
     pub(crate) open_settings: bool,
     pub(crate) open_about: bool,
     pub(crate) open_help: bool,
@@ -37,7 +292,10 @@ impl HanziApp {
     pub(crate) fn new(
         cc: &CreationContext<'_>,
         local_llm_models: Vec<String>,
-        selected_llm_model: String,
+        settings: settings::Settings,
+        converter: script::Converter,
+        hsk_index: hsk::Index,
+        dictionary: dictionary::Dictionary,
         database_url: String,
     ) -> Self {
         cc.egui_ctx.add_font(FontInsert::new(
@@ -54,27 +312,95 @@ impl HanziApp {
                 },
             ],
         ));
-        Self {
+        let mut app = Self {
             local_llm_models,
-            selected_llm_model,
+            selected_llm_model: settings.llm_model,
+            generation_options: settings.generation_options,
+            target_language: settings.target_language,
+            preferred_script: settings.preferred_script,
+            converter,
+            hsk_index,
+            dictionary,
+            hsk_version: settings.hsk_version,
+            hsk_filter: None,
+            hsk_coverage: Vec::new(),
+            speech: tts::backend(&settings.tts),
+            tts_settings: settings.tts,
+            tone_colors: settings.tone_colors,
+            pinyin_layout: settings.pinyin_layout,
+            speech_playback: None,
+            prompt_template_input: settings.prompt_templates.translate.clone(),
+            prompt_templates: settings.prompt_templates,
+            selected_prompt_kind: model::PromptKind::Translate,
+            template_validation: None,
             database_url,
             toasts: Toasts::default().with_anchor(Anchor::BottomRight),
             spinner: ModalSpinner::new()
                 .spinner_size(60.)
                 .spinner_color(egui::Color32::YELLOW),
             phrase_input: String::new(),
+            direction: model::Direction::default(),
             translation_input: String::new(),
             pinyin_input: String::new(),
-            llm_query: None,
-            llm_query_start: None,
+            senses_input: Vec::new(),
+            llm_queries: Vec::new(),
+            comparison_models: settings.comparison_models,
+            comparing: false,
+            max_attempts: settings.max_attempts,
+            candidates: Vec::new(),
+            examples_query: None,
+            examples_query_start: None,
+            examples_retry: llm::Retry::new(settings.max_attempts),
+            examples_count: 3,
+            candidate_examples: Vec::new(),
+            character: None,
+            stroke_animation: StrokeAnimation::default(),
+            practice: Practice::default(),
+            tone_drill: ToneDrill::default(),
+            quiz: Quiz::default(),
+            reader: Reader::default(),
+            statistics: model::Statistics::default(),
+            daily_goals: settings.daily_goals,
+            goal_progress: model::GoalProgress::default(),
+            study_start: None,
+            clipboard_watcher: settings
+                .clipboard
+                .watch
+                .then(|| clipboard::Watcher::start(cc.egui_ctx.clone())),
+            clipboard_settings: settings.clipboard,
+            clipboard_pending: None,
+            clipboard_lookup_at: None,
+            history: Vec::new(),
+            queue: Vec::new(),
+            queue_job: None,
+            queue_selection: HashSet::new(),
+            batch_input: String::new(),
+            batch_sources: HashMap::new(),
             phrase: None,
             phrases: Vec::new(),
+            open_examples: false,
+            open_batch: false,
+            open_character: false,
+            open_history: false,
+            open_hsk_coverage: false,
+            open_exercises: false,
+            open_practice: false,
+            open_quiz: false,
+            open_reader: false,
+            open_statistics: false,
+            open_tone_drill: false,
+            open_queue: false,
             open_settings: false,
             open_about: false,
             open_help: false,
             edit_result: false,
             is_macos: cc.egui_ctx.os() == OperatingSystem::Mac,
-        }
+        };
+        app.restore_queue();
+        app.reload_history();
+        app.reload_goal_progress();
+        app.remind_goals();
+        app
     }
 }
 
@@ -84,13 +410,9 @@ impl HanziApp {
             if self.edit_result {
                 phrase.translation = self.translation_input.clone();
                 phrase.pinyin = self.pinyin_input.clone();
+                phrase.senses = self.senses_input.clone();
             }
-            match persistence::write::phrase(
-                &self.database_url,
-                &phrase.original,
-                &phrase.pinyin,
-                &phrase.translation,
-            ) {
+            match store_phrase(&self.database_url, &self.converter, &self.hsk_index, phrase) {
                 Ok(_) => self
                     .toasts
                     .info("Phrase saved successfully")
@@ -104,6 +426,7 @@ impl HanziApp {
                         .show_progress_bar(true)
                 }
             };
+            self.reload_goal_progress();
         } else {
             self.toasts
                 .error("Nothing to save")
@@ -113,53 +436,509 @@ impl HanziApp {
     }
 
     pub(crate) fn save_settings(&mut self) {
-        match persistence::write::setting(
+        let settings = [
+            (model::SettingName::LlmModel, self.selected_llm_model.clone()),
+            (model::SettingName::TargetLanguage, self.target_language.clone()),
+            (model::SettingName::ComparisonModels, self.comparison_models.join(",")),
+            (model::SettingName::MaxAttempts, self.max_attempts.to_string()),
+            (model::SettingName::PreferredScript, self.preferred_script.to_string()),
+            (model::SettingName::HskVersion, self.hsk_version.to_string()),
+            (model::SettingName::TtsBackend, self.tts_settings.backend.to_string()),
+            (model::SettingName::TtsCommand, self.tts_settings.command.clone()),
+            (model::SettingName::TtsUrl, self.tts_settings.url.clone()),
+            (model::SettingName::ToneColoring, self.tone_colors.enabled.to_string()),
+            (model::SettingName::ToneColors, self.tone_colors.palette_to_string()),
+            (model::SettingName::PinyinLayout, self.pinyin_layout.to_string()),
+            (
+                model::SettingName::DailyNewPhrases,
+                self.daily_goals.new_phrases.to_string(),
+            ),
+            (model::SettingName::DailyReviews, self.daily_goals.reviews.to_string()),
+            (model::SettingName::DailyMinutes, self.daily_goals.minutes.to_string()),
+            (
+                model::SettingName::ClipboardWatch,
+                self.clipboard_settings.watch.to_string(),
+            ),
+            (
+                model::SettingName::ClipboardLlmFallback,
+                self.clipboard_settings.llm_fallback.to_string(),
+            ),
+            (
+                model::SettingName::ClipboardInterval,
+                self.clipboard_settings.interval_secs.to_string(),
+            ),
+            (
+                model::SettingName::ClipboardIgnore,
+                self.clipboard_settings.ignore.clone(),
+            ),
+        ]
+        .map(|(name, value)| (name.to_string(), value));
+        match persistence::write::settings(&self.database_url, &settings) {
+            Ok(_) => {
+                self.toasts
+                    .info("Settings saved successfully")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Settings could not be saved")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    fn save_setting(&mut self, name: model::SettingName, value: &str) {
+        if let Err(err) = persistence::write::setting(&self.database_url, &name.to_string(), value) {
+            log::error!("{}", err);
+            self.toasts
+                .error("Setting could not be saved")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+    }
+
+    pub(crate) fn change_llm_model(&mut self) {
+        self.save_settings();
+        self.generation_options = settings::load_generation_options(&self.database_url, &self.selected_llm_model);
+    }
+
+    pub(crate) fn save_generation_options(&mut self) {
+        match persistence::write::model_options(
             &self.database_url,
-            &model::SettingName::LlmModel.to_string(),
             &self.selected_llm_model,
+            self.generation_options.temperature,
+            self.generation_options.top_p,
+            self.generation_options.num_ctx,
+            self.generation_options.seed,
+            &self.generation_options.system_prompt,
         ) {
             Ok(_) => {
                 self.toasts
-                    .info("Settings saved successfully")
+                    .info("Model options saved successfully")
                     .duration(Some(Duration::from_secs(5)))
                     .show_progress_bar(true);
             }
             Err(err) => {
                 log::error!("{}", err);
                 self.toasts
-                    .error("Settings could not be saved")
+                    .error("Model options could not be saved")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn change_target_language(&mut self) {
+        self.save_settings();
+        if self.phrase.is_some() || !self.phrases.is_empty() {
+            self.load_phrases();
+        }
+    }
+
+    pub(crate) fn select_prompt_kind(&mut self, kind: model::PromptKind) {
+        self.selected_prompt_kind = kind;
+        self.prompt_template_input = self.prompt_templates.get(kind).to_owned();
+    }
+
+    pub(crate) fn save_prompt_template(&mut self) {
+        match persistence::write::prompt_template(
+            &self.database_url,
+            &self.selected_prompt_kind.to_string(),
+            &self.prompt_template_input,
+        ) {
+            Ok(_) => {
+                self.prompt_templates
+                    .set(self.selected_prompt_kind, self.prompt_template_input.clone());
+                self.toasts
+                    .info("Prompt template saved successfully")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Prompt template could not be saved")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn reset_prompt_template(&mut self) {
+        match persistence::delete::prompt_template(&self.database_url, &self.selected_prompt_kind.to_string()) {
+            Ok(_) => {
+                let default = llm::default_template(self.selected_prompt_kind).to_owned();
+                self.prompt_templates.set(self.selected_prompt_kind, default.clone());
+                self.prompt_template_input = default;
+                self.toasts
+                    .info("Prompt template reset to default")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Prompt template could not be reset")
                     .duration(Some(Duration::from_secs(5)))
                     .show_progress_bar(true);
             }
         }
     }
 
+    pub(crate) fn validate_prompt_template(&mut self) {
+        self.template_validation = Some(Promise::spawn_async(llm::validate_template(
+            self.selected_llm_model.clone(),
+            llm::ValidationQuery {
+                kind: self.selected_prompt_kind,
+                language: self.target_language.clone(),
+                template: self.prompt_template_input.clone(),
+                options: self.generation_options.clone(),
+            },
+        )));
+    }
+
     pub(crate) fn learn(&mut self) {
-        self.toasts
-            .info("This is where the learning mask will open")
-            .duration(Some(Duration::from_secs(5)))
-            .show_progress_bar(true);
+        self.open_exercises = !self.open_exercises;
+    }
+
+    pub(crate) fn start_exercise(&mut self, kind: model::ExerciseKind) {
+        self.open_exercises = false;
+        match kind {
+            model::ExerciseKind::Handwriting => self.practice_handwriting(),
+            model::ExerciseKind::ToneDrill => self.drill_tones(),
+            _ => self.start_quiz(kind),
+        }
+    }
+
+    fn saved_phrases(&self) -> Vec<model::Phrase> {
+        persistence::read::phrases(
+            &self.database_url,
+            "",
+            "",
+            &self.target_language,
+            self.hsk_filter.map(|level| (self.hsk_version, level)),
+        )
+        .inspect_err(|err| log::error!("Failed to load saved phrases: {}", err))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|stored| model::Phrase {
+            language: self.target_language.clone(),
+            ..model::Phrase::from(stored)
+        })
+        .collect()
+    }
+
+    /// Narrows a session to the phrase on screen, but only once it is saved.
+    fn session_phrases(&self, saved_phrases: &[model::Phrase]) -> Vec<model::Phrase> {
+        let current = self
+            .phrase
+            .as_ref()
+            .and_then(|phrase| saved_phrases.iter().find(|saved| saved.original == phrase.original));
+        match current {
+            Some(phrase) => vec![phrase.clone()],
+            None => saved_phrases.to_vec(),
+        }
+    }
+
+    pub(crate) fn start_quiz(&mut self, kind: model::ExerciseKind) {
+        let saved_phrases = self.saved_phrases();
+        let phrases = self.session_phrases(&saved_phrases);
+        let due = persistence::read::schedules(&self.database_url, &kind.to_string())
+            .inspect_err(|err| log::error!("{}", err))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(schedule, due)| (schedule.item, due))
+            .collect::<HashMap<_, _>>();
+        let cards = exercises::cards(kind, phrases, &saved_phrases, &due, PRACTICE_SIZE);
+        if cards.is_empty() {
+            self.toasts
+                .info(format!(
+                    "No phrases due for the {} exercise",
+                    kind.label().to_lowercase()
+                ))
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
+        }
+        self.quiz = Quiz {
+            kind,
+            cards,
+            ..Quiz::default()
+        };
+        self.open_quiz = true;
+    }
+
+    pub(crate) fn answer_quiz(&mut self, correct: bool) {
+        let Some(card) = self.quiz.card() else {
+            return;
+        };
+        if self.quiz.result.is_some() {
+            return;
+        }
+        let item = card.phrase.original.clone();
+        let exercise = self.quiz.kind.to_string();
+        let schedule = persistence::read::schedule(&self.database_url, &item, &exercise)
+            .map(|schedule| exercises::reschedule(schedule.map(model::Schedule::from), correct))
+            .and_then(|schedule| {
+                persistence::write::schedule(
+                    &self.database_url,
+                    &item,
+                    &exercise,
+                    schedule.repetitions,
+                    schedule.interval_days,
+                    schedule.ease,
+                )
+            })
+            .and_then(|_| record_review(&self.database_url, self.quiz.kind, &item, correct, !correct as i32));
+        if let Err(err) = schedule {
+            log::error!("{}", err);
+            self.toasts
+                .error("Exercise result could not be saved")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+        if correct {
+            self.quiz.correct += 1;
+        }
+        self.reload_goal_progress();
+        self.quiz.revealed = true;
+        self.quiz.result = Some(correct);
+    }
+
+    pub(crate) fn check_quiz_answer(&mut self) {
+        let Some(card) = self.quiz.card() else {
+            return;
+        };
+        if self.quiz.answer.trim().is_empty() {
+            return;
+        }
+        let correct = exercises::is_correct(&self.converter, &card.phrase.original, &self.quiz.answer);
+        self.answer_quiz(correct);
+    }
+
+    pub(crate) fn next_quiz_card(&mut self) {
+        if self.quiz.index + 1 < self.quiz.cards.len() {
+            self.quiz.index += 1;
+            self.quiz.answer = String::new();
+            self.quiz.revealed = false;
+            self.quiz.result = None;
+        } else {
+            self.open_quiz = false;
+            self.toasts
+                .info(format!(
+                    "{} finished, {} of {} correct",
+                    self.quiz.kind.label(),
+                    self.quiz.correct,
+                    self.quiz.cards.len()
+                ))
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+    }
+
+    pub(crate) fn practice_handwriting(&mut self) {
+        let text = self
+            .session_phrases(&self.saved_phrases())
+            .into_iter()
+            .map(|phrase| phrase.original)
+            .collect::<String>();
+        let mut seen = HashSet::new();
+        let characters = text
+            .chars()
+            .filter(|c| cjk::is_cjk(*c) && seen.insert(*c))
+            .filter_map(|c| {
+                characters::load(&self.database_url, &c.to_string())
+                    .inspect_err(|err| log::error!("{}", err))
+                    .ok()
+                    .flatten()
+            })
+            .filter(|character| !character.strokes.is_empty())
+            .take(PRACTICE_SIZE)
+            .collect::<Vec<_>>();
+        if characters.is_empty() {
+            self.toasts
+                .info("No characters with stroke data to practice, are the character data imported?")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
+        }
+        self.practice = Practice {
+            characters,
+            ..Practice::default()
+        };
+        self.open_practice = true;
+    }
+
+    pub(crate) fn finish_stroke(&mut self) {
+        let drawing = std::mem::take(&mut self.practice.drawing);
+        let Some(character) = self.practice.character() else {
+            return;
+        };
+        if self.practice.is_complete() {
+            return;
+        }
+        let feedback = handwriting::check_stroke(&drawing, &character.strokes, self.practice.step);
+        let item = character.character.clone();
+        if feedback == model::StrokeFeedback::Correct {
+            self.practice.step += 1;
+        } else {
+            self.practice.mistakes += 1;
+        }
+        self.practice.feedback = Some(feedback);
+        if !self.practice.is_complete() {
+            return;
+        }
+        match record_review(
+            &self.database_url,
+            model::ExerciseKind::Handwriting,
+            &item,
+            self.practice.mistakes == 0,
+            self.practice.mistakes as i32,
+        ) {
+            Ok(_) => self.reload_goal_progress(),
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Practice result could not be saved")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn restart_practice_character(&mut self) {
+        self.practice.step = 0;
+        self.practice.mistakes = 0;
+        self.practice.drawing = Vec::new();
+        self.practice.feedback = None;
+    }
+
+    pub(crate) fn next_practice_character(&mut self) {
+        if self.practice.index + 1 < self.practice.characters.len() {
+            self.practice.index += 1;
+            self.restart_practice_character();
+        } else {
+            self.open_practice = false;
+            self.toasts
+                .info("Practice finished")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+    }
+
+    pub(crate) fn drill_tones(&mut self) {
+        let phrases = self.session_phrases(&self.saved_phrases());
+        let attempts =
+            persistence::read::exercise_attempts(&self.database_url, &model::ExerciseKind::ToneDrill.to_string())
+                .inspect_err(|err| log::error!("{}", err))
+                .unwrap_or_default()
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+        let mut phrases = phrases
+            .into_iter()
+            .filter(|phrase| pinyin::tones(&phrase.original, &phrase.pinyin).is_some_and(|tones| !tones.is_empty()))
+            .collect::<Vec<_>>();
+        phrases.sort_by_key(|phrase| attempts.get(&phrase.original).copied().unwrap_or_default());
+        phrases.truncate(PRACTICE_SIZE);
+        if phrases.is_empty() {
+            self.toasts
+                .info("No saved phrases with pinyin to drill")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
+        }
+        self.tone_drill = ToneDrill {
+            phrases,
+            ..ToneDrill::default()
+        };
+        self.reload_tone_statistics();
+        self.open_tone_drill = true;
+    }
+
+    pub(crate) fn check_tone_drill(&mut self) {
+        let Some(phrase) = self.tone_drill.phrase() else {
+            return;
+        };
+        if self.tone_drill.is_checked() || self.tone_drill.answer.trim().is_empty() {
+            return;
+        }
+        let checks = drill::check(&phrase.pinyin, &self.tone_drill.answer);
+        let item = phrase.original.clone();
+        let mistakes = checks.iter().filter(|check| !check.is_correct()).count();
+        if let Err(err) = persistence::write::tone_answers(&self.database_url, &drill::answers(&item, &checks))
+            .and_then(|_| {
+                record_review(
+                    &self.database_url,
+                    model::ExerciseKind::ToneDrill,
+                    &item,
+                    mistakes == 0,
+                    mistakes as i32,
+                )
+            })
+        {
+            log::error!("{}", err);
+            self.toasts
+                .error("Drill result could not be saved")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+        self.tone_drill.checks = checks;
+        self.reload_tone_statistics();
+        self.reload_goal_progress();
+    }
+
+    pub(crate) fn next_tone_drill(&mut self) {
+        if self.tone_drill.index + 1 < self.tone_drill.phrases.len() {
+            self.tone_drill.index += 1;
+            self.tone_drill.answer = String::new();
+            self.tone_drill.checks = Vec::new();
+        } else {
+            self.open_tone_drill = false;
+            self.toasts
+                .info("Tone drill finished")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+    }
+
+    pub(crate) fn reload_tone_statistics(&mut self) {
+        match persistence::read::tone_answers(&self.database_url) {
+            Ok(answers) => self.tone_drill.statistics = drill::statistics(&answers),
+            Err(err) => log::error!("Failed to load tone statistics: {}", err),
+        }
     }
 
     pub(crate) fn load_phrases(&mut self) {
-        match persistence::read::phrases(&self.database_url, &self.phrase_input) {
+        let simplified_input = self.converter.convert(&self.phrase_input, model::Script::Simplified);
+        match persistence::read::phrases(
+            &self.database_url,
+            &self.phrase_input,
+            &simplified_input,
+            &self.target_language,
+            self.hsk_filter.map(|level| (self.hsk_version, level)),
+        ) {
             Ok(phrases) => {
+                let phrases = phrases
+                    .into_iter()
+                    .map(|stored| model::Phrase {
+                        language: self.target_language.clone(),
+                        ..model::Phrase::from(stored)
+                    })
+                    .collect::<Vec<_>>();
                 if phrases.is_empty() {
                     self.toasts
                         .info("Nothing found")
                         .duration(Some(Duration::from_secs(5)))
                         .show_progress_bar(true);
                 } else if phrases.len() == 1 {
-                    self.phrase = phrases
-                        .into_iter()
-                        .map(model::Phrase::from)
-                        .collect::<Vec<_>>()
-                        .first()
-                        .cloned();
+                    self.phrase = phrases.first().cloned();
                     self.phrases = Vec::new();
                 } else {
                     self.phrase = None;
-                    self.phrases = phrases.into_iter().map(model::Phrase::from).collect();
+                    self.phrases = phrases;
                 }
             }
             Err(err) => {
@@ -174,51 +953,1136 @@ impl HanziApp {
 
     pub(crate) fn query_llm(&mut self) {
         self.phrase = None;
-        self.llm_query = Some(Promise::spawn_async(llm::query(
-            self.selected_llm_model.clone(),
-            llm::Query {
-                text: self.phrase_input.to_owned(),
-            },
-        )));
-        self.llm_query_start = Some(Instant::now());
+        self.candidates = Vec::new();
+        let llm_models = std::iter::once(self.selected_llm_model.clone())
+            .chain(
+                self.comparison_models
+                    .iter()
+                    .filter(|llm_model| **llm_model != self.selected_llm_model)
+                    .filter(|llm_model| self.local_llm_models.contains(llm_model))
+                    .take(MAX_COMPARISON_MODELS)
+                    .cloned(),
+            )
+            .collect::<Vec<_>>();
+        self.comparing = llm_models.len() > 1;
+        self.llm_queries = llm_models
+            .into_iter()
+            .map(|llm_model| {
+                let options = if llm_model == self.selected_llm_model {
+                    self.generation_options.clone()
+                } else {
+                    settings::load_generation_options(&self.database_url, &llm_model)
+                };
+                let retry = llm::Retry::new(self.max_attempts);
+                TrackedQuery {
+                    input: self.phrase_input.to_owned(),
+                    promise: Promise::spawn_async(llm::query(
+                        llm_model.clone(),
+                        llm::Query {
+                            text: self.phrase_input.to_owned(),
+                            direction: self.direction,
+                            language: self.target_language.clone(),
+                            templates: self.prompt_templates.clone(),
+                            options,
+                            retry: retry.clone(),
+                        },
+                    )),
+                    llm_model,
+                    retry,
+                    start: Instant::now(),
+                }
+            })
+            .collect();
         self.spinner.open();
     }
 
-    pub(crate) fn handle_escape(&mut self) {
-        if let Some(q) = self.llm_query.take() {
-            q.abort();
-            self.llm_query = None;
-            self.llm_query_start = None;
-            self.spinner.close();
-        }
-        if self.open_about {
-            self.open_about = false;
-        }
-        if self.open_help {
-            self.open_help = false;
-        }
-        if self.open_settings {
-            self.open_settings = false;
+    pub(crate) fn toggle_comparison_model(&mut self, llm_model: &str) {
+        if let Some(index) = self.comparison_models.iter().position(|m| m == llm_model) {
+            self.comparison_models.remove(index);
+        } else if self.comparison_models.len() < MAX_COMPARISON_MODELS {
+            self.comparison_models.push(llm_model.to_owned());
+        } else {
+            self.toasts
+                .error(format!("At most {} models can be compared", MAX_COMPARISON_MODELS + 1))
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
         }
-        if self.edit_result {
-            self.edit();
+        self.save_settings();
+    }
+
+    pub(crate) fn pick_candidate(&mut self, index: usize) {
+        if let Some(Candidate { result: Ok(phrase), .. }) = self.candidates.get(index) {
+            self.phrase_input = phrase.original.clone();
+            self.phrase = Some(phrase.clone());
         }
     }
 
-    pub(crate) fn edit(&mut self) {
-        if self.edit_result {
-            self.edit_result = false;
-            self.phrase = Some(model::Phrase {
-                original: self.phrase_input.clone(),
-                pinyin: self.pinyin_input.clone(),
-                translation: self.translation_input.clone(),
-            });
-            self.translation_input = String::new();
-            self.pinyin_input = String::new();
-        } else if let Some(p) = &self.phrase {
-            self.edit_result = true;
-            self.translation_input = p.translation.clone();
-            self.pinyin_input = p.pinyin.clone();
+    pub(crate) fn merge_candidate(&mut self, index: usize) {
+        let Some(Candidate {
+            result: Ok(candidate), ..
+        }) = self.candidates.get(index)
+        else {
+            return;
+        };
+        match self.phrase.as_mut() {
+            Some(phrase) => {
+                if phrase.translation.is_empty() {
+                    phrase.translation = candidate.translation.clone();
+                }
+                candidate.senses.iter().for_each(|sense| {
+                    if !phrase
+                        .senses
+                        .iter()
+                        .any(|known| known.translation.eq_ignore_ascii_case(&sense.translation))
+                    {
+                        phrase.senses.push(sense.clone());
+                    }
+                });
+            }
+            None => self.pick_candidate(index),
         }
     }
+
+    pub(crate) fn generate_examples(&mut self) {
+        if let Some(phrase) = &self.phrase {
+            self.candidate_examples = Vec::new();
+            self.examples_retry = llm::Retry::new(self.max_attempts);
+            self.examples_query = Some(Promise::spawn_async(llm::examples(
+                self.selected_llm_model.clone(),
+                llm::ExamplesQuery {
+                    text: phrase.original.clone(),
+                    language: phrase.language.clone(),
+                    count: self.examples_count,
+                    template: self.prompt_templates.examples.clone(),
+                    options: self.generation_options.clone(),
+                    retry: self.examples_retry.clone(),
+                },
+            )));
+            self.examples_query_start = Some(Instant::now());
+            self.open_examples = true;
+            self.spinner.open();
+        } else {
+            self.toasts
+                .error("Nothing to generate examples for")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+    }
+
+    pub(crate) fn keep_example(&mut self, index: usize) {
+        let Some(phrase) = self.phrase.as_mut() else {
+            return;
+        };
+        let example = self.candidate_examples.remove(index);
+        match persistence::write::example(
+            &self.database_url,
+            &phrase.original,
+            &example.hanzi,
+            &example.pinyin,
+            &example.translation,
+            &phrase.language,
+        ) {
+            Ok(_) => {
+                phrase.examples.push(example);
+                self.toasts
+                    .info("Example saved successfully")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.candidate_examples.insert(index, example);
+                self.toasts
+                    .error("Example could not be saved, is the phrase saved?")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn discard_example(&mut self, index: usize) {
+        self.candidate_examples.remove(index);
+    }
+
+    pub(crate) fn enqueue_batch(&mut self) {
+        let texts = self
+            .batch_input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| (line.to_owned(), self.batch_sources.get(line).copied()))
+            .collect::<Vec<_>>();
+        if texts.is_empty() {
+            self.toasts
+                .error("Nothing to enqueue")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
+        }
+        match persistence::write::queue_items(
+            &self.database_url,
+            &texts,
+            &self.target_language,
+            &self.direction.to_string(),
+            &self.selected_llm_model,
+            &model::QueueStatus::Pending.to_string(),
+        ) {
+            Ok(count) => {
+                self.batch_input = String::new();
+                self.batch_sources.clear();
+                self.open_batch = false;
+                self.open_queue = true;
+                self.reload_queue();
+                self.toasts
+                    .info(format!("{} phrases added to the queue", count))
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Phrases could not be added to the queue")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn reload_queue(&mut self) {
+        match persistence::read::queue_items(&self.database_url) {
+            Ok(items) => {
+                self.queue = items.into_iter().map(model::QueueItem::from).collect();
+                self.queue_selection
+                    .retain(|id| self.queue.iter().any(|item| item.id == *id));
+            }
+            Err(err) => log::error!("Failed to load the queue: {}", err),
+        }
+    }
+
+    pub(crate) fn process_queue(&mut self) {
+        if self.queue_job.is_some() {
+            return;
+        }
+        let Some(item) = self
+            .queue
+            .iter()
+            .find(|item| item.status == model::QueueStatus::Pending)
+        else {
+            return;
+        };
+        let (id, text, language, direction) = (item.id, item.text.clone(), item.language.clone(), item.direction);
+        let llm_model = if item.llm_model.is_empty() {
+            self.selected_llm_model.clone()
+        } else {
+            item.llm_model.clone()
+        };
+        self.update_queue_item(id, model::QueueStatus::Running, None, None);
+        self.queue_job = Some(QueueJob {
+            id,
+            llm_model: llm_model.clone(),
+            promise: Promise::spawn_async(llm::query(
+                llm_model,
+                llm::Query {
+                    text,
+                    direction,
+                    language,
+                    templates: self.prompt_templates.clone(),
+                    options: self.generation_options.clone(),
+                    retry: llm::Retry::new(self.max_attempts),
+                },
+            )),
+            start: Instant::now(),
+        });
+    }
+
+    pub(crate) fn complete_queue_job(
+        &mut self,
+        id: i32,
+        llm_model: &str,
+        result: Result<model::Phrase, String>,
+        duration: Duration,
+    ) {
+        if let Some(item) = self.queue.iter().find(|item| item.id == id) {
+            let input = item.text.clone();
+            self.record_lookup(&input, llm_model, result.as_ref(), duration);
+        }
+        match result {
+            Ok(phrase) => match serde_json::to_string(&phrase) {
+                Ok(json) => self.update_queue_item(id, model::QueueStatus::Done, Some(&json), None),
+                Err(err) => self.update_queue_item(id, model::QueueStatus::Failed, None, Some(&err.to_string())),
+            },
+            Err(err) => self.update_queue_item(id, model::QueueStatus::Failed, None, Some(&err)),
+        }
+    }
+
+    pub(crate) fn review_queue_item(&mut self, id: i32) {
+        if let Some(phrase) = self
+            .queue
+            .iter()
+            .find(|item| item.id == id)
+            .and_then(|item| item.result.clone())
+        {
+            self.phrase_input = phrase.original.clone();
+            self.phrase = Some(phrase);
+            self.phrases = Vec::new();
+            self.candidates = Vec::new();
+            self.edit_result = false;
+        }
+    }
+
+    pub(crate) fn save_queue_selection(&mut self) {
+        let selected = self
+            .queue
+            .iter()
+            .filter(|item| self.queue_selection.contains(&item.id))
+            .filter_map(|item| item.result.clone().map(|phrase| (item.id, phrase)))
+            .collect::<Vec<_>>();
+        if selected.is_empty() {
+            self.toasts
+                .error("Nothing to save")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
+        }
+        let mut saved = 0;
+        selected.into_iter().for_each(|(id, phrase)| {
+            match store_phrase(&self.database_url, &self.converter, &self.hsk_index, &phrase).and_then(|_| {
+                persistence::write::queue_item_status(
+                    &self.database_url,
+                    id,
+                    &model::QueueStatus::Saved.to_string(),
+                    serde_json::to_string(&phrase).ok().as_deref(),
+                    None,
+                )
+            }) {
+                Ok(_) => saved += 1,
+                Err(err) => log::error!("Failed to save queue item {}: {}", id, err),
+            }
+        });
+        self.queue_selection.clear();
+        self.reload_queue();
+        self.reload_goal_progress();
+        self.toasts
+            .info(format!("{} phrases saved", saved))
+            .duration(Some(Duration::from_secs(5)))
+            .show_progress_bar(true);
+    }
+
+    pub(crate) fn retry_queue_item(&mut self, id: i32) {
+        self.update_queue_item(id, model::QueueStatus::Pending, None, None);
+    }
+
+    pub(crate) fn remove_queue_item(&mut self, id: i32) {
+        if let Some(job) = self.queue_job.take_if(|job| job.id == id) {
+            job.promise.abort();
+        }
+        match persistence::delete::queue_item(&self.database_url, id) {
+            Ok(_) => self.reload_queue(),
+            Err(err) => log::error!("{}", err),
+        }
+    }
+
+    pub(crate) fn clear_saved_queue_items(&mut self) {
+        match persistence::delete::queue_items(&self.database_url, &model::QueueStatus::Saved.to_string()) {
+            Ok(_) => self.reload_queue(),
+            Err(err) => log::error!("{}", err),
+        }
+    }
+
+    fn restore_queue(&mut self) {
+        if let Err(err) = persistence::write::queue_items_status(
+            &self.database_url,
+            &model::QueueStatus::Running.to_string(),
+            &model::QueueStatus::Pending.to_string(),
+        ) {
+            log::error!("Failed to restore interrupted queue items: {}", err);
+        }
+        self.reload_queue();
+    }
+
+    fn update_queue_item(&mut self, id: i32, status: model::QueueStatus, result: Option<&str>, error: Option<&str>) {
+        match persistence::write::queue_item_status(&self.database_url, id, &status.to_string(), result, error) {
+            Ok(_) => self.reload_queue(),
+            Err(err) => log::error!("{}", err),
+        }
+    }
+
+    pub(crate) fn record_lookup(
+        &mut self,
+        input: &str,
+        llm_model: &str,
+        result: Result<&model::Phrase, &String>,
+        duration: Duration,
+    ) {
+        let (language, json, error) = match result {
+            Ok(phrase) => (phrase.language.as_str(), serde_json::to_string(phrase).ok(), None),
+            Err(err) => (self.target_language.as_str(), None, Some(err.as_str())),
+        };
+        match persistence::write::lookup(
+            &self.database_url,
+            input,
+            llm_model,
+            language,
+            json.as_deref(),
+            error,
+            duration.as_millis() as i32,
+        )
+        .and_then(|_| persistence::write::event(&self.database_url, &model::EventKind::Lookup.to_string(), input, 0))
+        {
+            Ok(_) => self.reload_history(),
+            Err(err) => log::error!("Failed to record lookup: {}", err),
+        }
+    }
+
+    pub(crate) fn reload_history(&mut self) {
+        match persistence::read::lookups(&self.database_url, HISTORY_LIMIT) {
+            Ok(lookups) => self.history = lookups.into_iter().map(model::Lookup::from).collect(),
+            Err(err) => log::error!("Failed to load the history: {}", err),
+        }
+    }
+
+    pub(crate) fn reopen_lookup(&mut self, index: usize) {
+        if let Some(lookup) = self.history.get(index) {
+            self.phrase_input = lookup
+                .result
+                .as_ref()
+                .map(|phrase| phrase.original.clone())
+                .unwrap_or(lookup.input.clone());
+            self.phrase = lookup.result.clone();
+            self.phrases = Vec::new();
+            self.candidates = Vec::new();
+            self.edit_result = false;
+        }
+    }
+
+    pub(crate) fn save_lookup(&mut self, index: usize) {
+        let Some(phrase) = self.history.get(index).and_then(|lookup| lookup.result.as_ref()) else {
+            return;
+        };
+        match store_phrase(&self.database_url, &self.converter, &self.hsk_index, phrase) {
+            Ok(_) => self
+                .toasts
+                .info("Phrase saved successfully")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true),
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Phrase could not be saved")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true)
+            }
+        };
+        self.reload_goal_progress();
+    }
+
+    pub(crate) fn clear_history(&mut self) {
+        match persistence::delete::lookups(&self.database_url) {
+            Ok(_) => self.history = Vec::new(),
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("History could not be cleared")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn average_durations(&self) -> Vec<(String, Duration)> {
+        let mut durations = std::collections::BTreeMap::<&str, Vec<Duration>>::new();
+        self.history
+            .iter()
+            .filter(|lookup| lookup.result.is_some())
+            .for_each(|lookup| durations.entry(&lookup.llm_model).or_default().push(lookup.duration));
+        durations
+            .into_iter()
+            .map(|(llm_model, durations)| {
+                (
+                    llm_model.to_owned(),
+                    durations.iter().sum::<Duration>() / durations.len() as u32,
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn show_character(&mut self, character: &str) {
+        match characters::load(&self.database_url, character) {
+            Ok(Some(details)) => {
+                self.character = Some(details);
+                self.stroke_animation.replay();
+                self.open_character = true;
+            }
+            Ok(None) => {
+                let message = match characters::dictionary_file(&self.database_url) {
+                    Some(file) if !file.exists() => format!(
+                        "No details found for {}, place makemeahanzi's dictionary.txt at {} and restart",
+                        character,
+                        file.display()
+                    ),
+                    _ => format!("No details found for {}", character),
+                };
+                self.toasts
+                    .info(message)
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Character details could not be loaded")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn reload_goal_progress(&mut self) {
+        match goals::progress(&self.database_url, &self.daily_goals) {
+            Ok(progress) => self.goal_progress = progress,
+            Err(err) => log::error!("Failed to load the progress of the daily goals: {}", err),
+        }
+    }
+
+    pub(crate) fn remind_goals(&mut self) {
+        if !self.daily_goals.is_set() || self.daily_goals.is_met(&self.goal_progress) {
+            return;
+        }
+        let streak = if self.goal_progress.streak > 0 {
+            format!(", keep your {} day streak going", self.goal_progress.streak)
+        } else {
+            String::new()
+        };
+        self.toasts
+            .info(format!(
+                "Today so far: {} of {} new phrases, {} of {} reviews, {} of {} minutes{}",
+                self.goal_progress.new_phrases,
+                self.daily_goals.new_phrases,
+                self.goal_progress.reviews,
+                self.daily_goals.reviews,
+                self.goal_progress.minutes,
+                self.daily_goals.minutes,
+                streak
+            ))
+            .duration(Some(Duration::from_secs(10)))
+            .show_progress_bar(true);
+    }
+
+    pub(crate) fn track_study(&mut self) {
+        let studying = if self.open_quiz {
+            Some(self.quiz.kind)
+        } else if self.open_tone_drill {
+            Some(model::ExerciseKind::ToneDrill)
+        } else if self.open_practice {
+            Some(model::ExerciseKind::Handwriting)
+        } else {
+            None
+        };
+        match (studying, self.study_start) {
+            (Some(kind), None) => self.study_start = Some((Instant::now(), kind)),
+            (None, Some(_)) => self.finish_study(),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn studied_minutes(&self) -> u32 {
+        let studying = self
+            .study_start
+            .map(|(start, _)| start.elapsed().as_secs() / 60)
+            .unwrap_or_default();
+        self.goal_progress.minutes + studying as u32
+    }
+
+    pub(crate) fn finish_study(&mut self) {
+        let Some((start, kind)) = self.study_start.take() else {
+            return;
+        };
+        match persistence::write::event(
+            &self.database_url,
+            &model::EventKind::Study.to_string(),
+            &kind.to_string(),
+            start.elapsed().as_millis() as i32,
+        ) {
+            Ok(_) => self.reload_goal_progress(),
+            Err(err) => log::error!("Failed to record study time: {}", err),
+        }
+    }
+
+    pub(crate) fn toggle_clipboard_watch(&mut self, ctx: &egui::Context) {
+        self.clipboard_settings.watch = !self.clipboard_settings.watch;
+        self.watch_clipboard(ctx);
+        self.save_setting(
+            model::SettingName::ClipboardWatch,
+            &self.clipboard_settings.watch.to_string(),
+        );
+    }
+
+    pub(crate) fn watch_clipboard(&mut self, ctx: &egui::Context) {
+        if !self.clipboard_settings.watch {
+            self.clipboard_watcher = None;
+            self.clipboard_pending = None;
+        } else if self.clipboard_watcher.is_none() {
+            self.clipboard_watcher = Some(clipboard::Watcher::start(ctx.clone()));
+        }
+    }
+
+    pub(crate) fn poll_clipboard(&mut self, ctx: &egui::Context) {
+        let Some(watcher) = &self.clipboard_watcher else {
+            return;
+        };
+        if !watcher.is_running() {
+            self.clipboard_watcher = None;
+            self.clipboard_settings.watch = false;
+            self.toasts
+                .error("The clipboard could not be watched")
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+            return;
+        }
+        if let Some(text) = watcher
+            .copied()
+            .filter_map(|text| clipboard::candidate(&text, &self.clipboard_settings))
+            .last()
+        {
+            self.clipboard_pending = Some(text);
+        }
+        let Some(text) = self.clipboard_pending.take() else {
+            return;
+        };
+        if text == self.phrase_input.trim() {
+            return;
+        }
+        let interval = Duration::from_secs(self.clipboard_settings.interval_secs as u64);
+        let wait = self
+            .clipboard_lookup_at
+            .map(|lookup_at| interval.saturating_sub(lookup_at.elapsed()))
+            .unwrap_or_default();
+        if !wait.is_zero() || !self.llm_queries.is_empty() || self.examples_query.is_some() {
+            self.clipboard_pending = Some(text);
+            ctx.request_repaint_after(wait);
+            return;
+        }
+        self.clipboard_lookup_at = Some(Instant::now());
+        self.lookup_copied(text);
+    }
+
+    pub(crate) fn lookup_copied(&mut self, text: String) {
+        self.phrase_input = text.clone();
+        self.phrases = Vec::new();
+        self.candidates = Vec::new();
+        self.edit_result = false;
+        let start = Instant::now();
+        if let Some(phrase) = self.dictionary.phrase(&text) {
+            self.record_lookup(&text, dictionary::SOURCE, Ok(&phrase), start.elapsed());
+            self.phrase = Some(phrase);
+        } else if self.clipboard_settings.llm_fallback {
+            self.query_llm();
+        } else {
+            self.phrase = None;
+            let message = if self.dictionary.is_empty() {
+                format!("No dictionary available to look up {}", text)
+            } else {
+                format!("No dictionary entry for {}", text)
+            };
+            self.toasts
+                .info(message)
+                .duration(Some(Duration::from_secs(5)))
+                .show_progress_bar(true);
+        }
+    }
+
+    pub(crate) fn toggle_reader(&mut self) {
+        if !self.open_reader {
+            self.reload_known_words();
+            self.reload_documents();
+        }
+        self.open_reader = !self.open_reader;
+    }
+
+    pub(crate) fn reload_documents(&mut self) {
+        match persistence::read::documents(&self.database_url) {
+            Ok(documents) => self.reader.documents = documents.into_iter().map(model::Document::from).collect(),
+            Err(err) => log::error!("Failed to load the documents: {}", err),
+        }
+    }
+
+    pub(crate) fn open_document(&mut self, document_id: i32) {
+        let Some(document) = self
+            .reader
+            .documents
+            .iter()
+            .find(|document| document.id == document_id)
+            .cloned()
+        else {
+            return;
+        };
+        match persistence::read::document_lines(&self.database_url, document_id) {
+            Ok(lines) => {
+                let lines = lines.into_iter().map(model::DocumentLine::from).collect::<Vec<_>>();
+                self.reader.text = lines
+                    .iter()
+                    .map(|line| line.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.read_text();
+                self.reader.path = document.path.clone();
+                self.reader.document = Some(document);
+                self.reader.lines = lines;
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error(format!("{} could not be opened", document.title))
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn queue_document(&mut self, document_id: i32) {
+        match persistence::read::document_lines(&self.database_url, document_id) {
+            Ok(lines) => {
+                self.batch_input = lines
+                    .iter()
+                    .map(|line| line.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.batch_sources = HashMap::new();
+                lines.into_iter().for_each(|line| {
+                    self.batch_sources.entry(line.text.trim().to_owned()).or_insert(line.id);
+                });
+                self.open_batch = true;
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("The lines of the document could not be loaded")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn delete_document(&mut self, document_id: i32) {
+        match persistence::delete::document(&self.database_url, document_id) {
+            Ok(_) => self.reload_documents(),
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Document could not be deleted")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn open_phrase_source(&mut self, line_id: i32) {
+        let line = match persistence::read::document_line(&self.database_url, line_id) {
+            Ok(Some(line)) => model::DocumentLine::from(line),
+            Ok(None) => {
+                self.toasts
+                    .info("The source of the phrase is no longer available")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+                return;
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                return;
+            }
+        };
+        self.reload_known_words();
+        self.reload_documents();
+        self.open_document(line.document_id);
+        if let Some(index) = self
+            .reader
+            .segments
+            .iter()
+            .position(|segment| segment.is_word && segment.line == line.position)
+        {
+            self.select_reader_word(index);
+            self.reader.scroll_to_selection = true;
+        }
+        self.open_reader = true;
+    }
+
+    pub(crate) fn reload_known_words(&mut self) {
+        match persistence::read::phrase_originals(&self.database_url) {
+            Ok(originals) => {
+                self.reader.known_words = originals
+                    .into_iter()
+                    .flat_map(|original| {
+                        [
+                            self.converter.convert(&original, model::Script::Simplified),
+                            self.converter.convert(&original, model::Script::Traditional),
+                            original,
+                        ]
+                    })
+                    .collect();
+            }
+            Err(err) => log::error!("Failed to load the known words: {}", err),
+        }
+    }
+
+    pub(crate) fn import_document(&mut self) {
+        let path = self.reader.path.trim().to_owned();
+        match documents::import(&self.database_url, &path) {
+            Ok(document_id) => {
+                self.reload_documents();
+                self.open_document(document_id);
+            }
+            Err(err) => {
+                log::error!("Failed to import {}: {}", path, err);
+                self.toasts
+                    .error(err.to_string())
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn read_text(&mut self) {
+        let (sentences, segments) = reader::segment(&self.reader.text, &self.dictionary, &self.reader.known_words);
+        self.reader.document = None;
+        self.reader.lines = Vec::new();
+        self.reader.sentences = sentences;
+        self.reader.segments = segments;
+        self.reader.selected = None;
+        self.reader.gloss = None;
+        self.reader.gloss_query = None;
+    }
+
+    pub(crate) fn edit_reader_text(&mut self) {
+        self.reader.document = None;
+        self.reader.lines = Vec::new();
+        self.reader.sentences = Vec::new();
+        self.reader.segments = Vec::new();
+        self.reader.selected = None;
+        self.reader.gloss = None;
+        self.reader.gloss_query = None;
+    }
+
+    pub(crate) fn select_reader_word(&mut self, index: usize) {
+        if let Some(query) = self.reader.gloss_query.take() {
+            query.promise.abort();
+        }
+        self.reader.selected = Some(index);
+        self.reader.gloss = self.reader.word().and_then(|word| self.dictionary.phrase(word));
+    }
+
+    pub(crate) fn ask_reader_gloss(&mut self) {
+        let Some(word) = self.reader.word().map(str::to_owned) else {
+            return;
+        };
+        let retry = llm::Retry::new(self.max_attempts);
+        self.reader.gloss_query = Some(TrackedQuery {
+            input: word.clone(),
+            promise: Promise::spawn_async(llm::query(
+                self.selected_llm_model.clone(),
+                llm::Query {
+                    text: word,
+                    direction: model::Direction::FromChinese,
+                    language: self.target_language.clone(),
+                    templates: self.prompt_templates.clone(),
+                    options: self.generation_options.clone(),
+                    retry: retry.clone(),
+                },
+            )),
+            llm_model: self.selected_llm_model.clone(),
+            retry,
+            start: Instant::now(),
+        });
+    }
+
+    pub(crate) fn poll_reader_gloss(&mut self) {
+        let Some(query) = self.reader.gloss_query.take() else {
+            return;
+        };
+        let TrackedQuery {
+            input,
+            llm_model,
+            promise,
+            retry,
+            start,
+        } = query;
+        match promise.try_take() {
+            Ok(Ok(mut phrase)) => {
+                self.record_lookup(&input, &llm_model, Ok(&phrase), start.elapsed());
+                phrase.original = input;
+                self.reader.gloss = Some(phrase);
+            }
+            Ok(Err(err)) => {
+                log::error!("Error occurred when querying LLM {}: {}", llm_model, err.cause());
+                self.record_lookup(&input, &llm_model, Err(&err.cause()), start.elapsed());
+                self.toasts
+                    .error(format!("Querying LLM failed: {}", err.cause()))
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Err(promise) => {
+                self.reader.gloss_query = Some(TrackedQuery {
+                    input,
+                    llm_model,
+                    promise,
+                    retry,
+                    start,
+                });
+            }
+        }
+    }
+
+    pub(crate) fn save_reader_word(&mut self) {
+        let Some(mut phrase) = self.reader.gloss.clone() else {
+            return;
+        };
+        let sentence = self
+            .reader
+            .sentence()
+            .filter(|sentence| *sentence != phrase.original)
+            .map(str::to_owned);
+        phrase.document_line = self.reader.source_line().map(|line| line.id);
+        match store_phrase(&self.database_url, &self.converter, &self.hsk_index, &phrase).and_then(
+            |_| match &sentence {
+                Some(sentence) => persistence::write::example(
+                    &self.database_url,
+                    &phrase.original,
+                    sentence,
+                    "",
+                    "",
+                    &phrase.language,
+                )
+                .map(|_| ()),
+                None => Ok(()),
+            },
+        ) {
+            Ok(_) => {
+                let message = match sentence {
+                    Some(_) => format!("{} saved with its sentence", phrase.original),
+                    None => format!("{} saved", phrase.original),
+                };
+                self.toasts
+                    .info(message)
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+                self.reload_known_words();
+                self.reload_goal_progress();
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("Phrase could not be saved")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn show_statistics(&mut self) {
+        if self.open_statistics {
+            self.open_statistics = false;
+            return;
+        }
+        match statistics::load(&self.database_url) {
+            Ok(statistics) => {
+                self.statistics = statistics;
+                self.open_statistics = true;
+            }
+            Err(err) => {
+                log::error!("Failed to load statistics: {}", err);
+                self.toasts
+                    .error("Failed to load statistics")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn show_hsk_coverage(&mut self) {
+        if self.open_hsk_coverage {
+            self.open_hsk_coverage = false;
+            return;
+        }
+        match persistence::read::hsk_coverage(&self.database_url, &self.hsk_version.to_string()) {
+            Ok(coverage) if coverage.is_empty() => {
+                let message = match hsk::dir(&self.database_url) {
+                    Some(dir) => format!(
+                        "No {} word lists imported, place them as hsk{}-<level>.txt into {}",
+                        self.hsk_version.label(),
+                        self.hsk_version.major(),
+                        dir.display()
+                    ),
+                    None => format!("No {} word lists imported", self.hsk_version.label()),
+                };
+                self.toasts
+                    .info(message)
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+            Ok(coverage) => {
+                self.hsk_coverage = coverage;
+                self.open_hsk_coverage = true;
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                self.toasts
+                    .error("HSK coverage could not be loaded")
+                    .duration(Some(Duration::from_secs(5)))
+                    .show_progress_bar(true);
+            }
+        }
+    }
+
+    pub(crate) fn ruby(&self, hanzi: &str, pinyin: &str) -> Option<Vec<model::Ruby>> {
+        match self.pinyin_layout {
+            model::PinyinLayout::Column => None,
+            model::PinyinLayout::Ruby => pinyin::ruby(hanzi, pinyin),
+        }
+    }
+
+    pub(crate) fn change_tts_settings(&mut self) {
+        self.save_settings();
+        self.speech = tts::backend(&self.tts_settings);
+    }
+
+    pub(crate) fn speak(&mut self, text: &str) {
+        if self.speech_playback.is_some() {
+            return;
+        }
+        let backend = self.speech.clone();
+        let cache_dir = tts::cache_dir(&self.database_url);
+        let text = text.to_owned();
+        self.speech_playback = Some(Promise::spawn_thread("speech", move || {
+            tts::speak(backend, cache_dir, text)
+        }));
+    }
+
+    pub(crate) fn handle_escape(&mut self) {
+        if !self.llm_queries.is_empty() {
+            self.llm_queries.drain(..).for_each(|q| q.promise.abort());
+            self.spinner.close();
+        }
+        if let Some(q) = self.examples_query.take() {
+            q.abort();
+            self.examples_query = None;
+            self.examples_query_start = None;
+            self.spinner.close();
+        }
+        if self.open_examples {
+            self.open_examples = false;
+        }
+        if self.open_batch {
+            self.open_batch = false;
+        }
+        if self.open_character {
+            self.open_character = false;
+        }
+        if self.open_exercises {
+            self.open_exercises = false;
+        }
+        if self.open_practice {
+            self.open_practice = false;
+        }
+        if self.open_quiz {
+            self.open_quiz = false;
+        }
+        if self.open_statistics {
+            self.open_statistics = false;
+        }
+        if self.open_reader {
+            self.open_reader = false;
+        }
+        if self.open_tone_drill {
+            self.open_tone_drill = false;
+        }
+        if self.open_hsk_coverage {
+            self.open_hsk_coverage = false;
+        }
+        if self.open_about {
+            self.open_about = false;
+        }
+        if self.open_help {
+            self.open_help = false;
+        }
+        if self.open_settings {
+            self.open_settings = false;
+        }
+        if self.edit_result {
+            self.edit();
+        }
+    }
+
+    pub(crate) fn edit(&mut self) {
+        if self.edit_result {
+            self.edit_result = false;
+            let previous = self.phrase.take().unwrap_or_default();
+            self.phrase = Some(model::Phrase {
+                original: self.phrase_input.clone(),
+                pinyin: self.pinyin_input.clone(),
+                language: previous.language,
+                translation: self.translation_input.clone(),
+                senses: std::mem::take(&mut self.senses_input),
+                examples: previous.examples,
+                document_line: previous.document_line,
+            });
+            self.translation_input = String::new();
+            self.pinyin_input = String::new();
+        } else if let Some(p) = &self.phrase {
+            self.edit_result = true;
+            self.translation_input = p.translation.clone();
+            self.pinyin_input = p.pinyin.clone();
+            self.senses_input = p.senses.clone();
+        }
+    }
+}
+
+fn record_review(
+    database_url: &str,
+    exercise: model::ExerciseKind,
+    item: &str,
+    correct: bool,
+    mistakes: i32,
+) -> Result<usize, persistence::PersistenceError> {
+    persistence::write::exercise_result(database_url, &exercise.to_string(), item, correct, mistakes)
+        .and_then(|_| persistence::write::event(database_url, &model::EventKind::Review.to_string(), item, 0))
+}
+
+fn store_phrase(
+    database_url: &str,
+    converter: &script::Converter,
+    hsk_index: &hsk::Index,
+    phrase: &model::Phrase,
+) -> Result<bool, persistence::PersistenceError> {
+    let senses = phrase
+        .senses
+        .iter()
+        .filter(|sense| !sense.translation.trim().is_empty())
+        .map(|sense| (sense.translation.clone(), sense.note.clone()))
+        .collect::<Vec<_>>();
+    let (simplified, traditional) = if converter.is_available() {
+        (
+            converter.convert(&phrase.original, model::Script::Simplified),
+            converter.convert(&phrase.original, model::Script::Traditional),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+    let (hsk2_level, hsk3_level) = hsk::levels(hsk_index, converter, &phrase.original);
+    let new_phrase = persistence::model::NewPhrase {
+        original: phrase.original.clone(),
+        pinyin: phrase.pinyin.clone(),
+        simplified,
+        traditional,
+        hsk2_level,
+        hsk3_level,
+        document_line_id: phrase.document_line,
+    };
+    persistence::write::phrase(
+        database_url,
+        &new_phrase,
+        &phrase.language,
+        &phrase.translation,
+        &senses,
+        &model::EventKind::Save.to_string(),
+    )
 }
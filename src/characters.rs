@@ -0,0 +1,337 @@
+use serde::Deserialize;
+use std::io::BufRead;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::cjk;
+use crate::model;
+use crate::persistence;
+
+const DICTIONARY_FILE: &str = "dictionary.txt";
+const GRAPHICS_FILE: &str = "graphics.txt";
+const RELATED_LIMIT: i64 = 24;
+const CURVE_SEGMENTS: usize = 8;
+
+#[derive(Deserialize)]
+struct DictionaryEntry {
+    character: String,
+    #[serde(default)]
+    definition: Option<String>,
+    #[serde(default)]
+    pinyin: Vec<String>,
+    #[serde(default)]
+    decomposition: String,
+    #[serde(default)]
+    radical: String,
+}
+
+#[derive(Deserialize)]
+struct GraphicsEntry {
+    character: String,
+    strokes: Vec<String>,
+    #[serde(default)]
+    medians: Vec<Vec<[f32; 2]>>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum CharacterError {
+    #[error("Reading character data failed")]
+    Io(#[from] std::io::Error),
+    #[error("Parsing character data failed in line {0}: {1}")]
+    Parse(usize, serde_json::Error),
+    #[error("Storing character data failed")]
+    Persistence(#[from] persistence::PersistenceError),
+}
+
+pub(crate) fn graphics_file(database_url: &str) -> Option<PathBuf> {
+    Path::new(database_url)
+        .parent()
+        .map(|data_dir| data_dir.join(GRAPHICS_FILE))
+}
+
+pub(crate) fn dictionary_file(database_url: &str) -> Option<PathBuf> {
+    Path::new(database_url)
+        .parent()
+        .map(|data_dir| data_dir.join(DICTIONARY_FILE))
+}
+
+pub(crate) fn import_if_missing(database_url: &str) {
+    let Some(data_dir) = Path::new(database_url).parent() else {
+        return;
+    };
+    if !data_dir.join(DICTIONARY_FILE).exists() {
+        log::info!(
+            "No character data found, place makemeahanzi's {} into {} to enable character details",
+            DICTIONARY_FILE,
+            data_dir.display()
+        );
+    } else {
+        match persistence::read::characters_count(database_url) {
+            Ok(0) => match import_dictionary(database_url, data_dir) {
+                Ok(count) => log::info!("Imported {} characters from {}", count, data_dir.display()),
+                Err(err) => log::error!("Failed to import characters: {}", err),
+            },
+            Ok(_) => {}
+            Err(err) => log::error!("Failed to check for character data: {}", err),
+        }
+    }
+    if !data_dir.join(GRAPHICS_FILE).exists() {
+        log::info!(
+            "No stroke data found, place makemeahanzi's {} into {} to enable stroke order",
+            GRAPHICS_FILE,
+            data_dir.display()
+        );
+    } else {
+        match persistence::read::strokes_count(database_url) {
+            Ok(0) => match import_strokes(database_url, data_dir) {
+                Ok(count) => log::info!("Imported {} strokes from {}", count, data_dir.display()),
+                Err(err) => log::error!("Failed to import strokes: {}", err),
+            },
+            Ok(_) => {}
+            Err(err) => log::error!("Failed to check for stroke data: {}", err),
+        }
+    }
+    match persistence::write::stroke_counts(database_url) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Updated the stroke counts of {} characters", count),
+        Err(err) => log::error!("Failed to update stroke counts: {}", err),
+    }
+}
+
+pub(crate) fn import_dictionary(database_url: &str, data_dir: &Path) -> Result<usize, CharacterError> {
+    let file = std::fs::File::open(data_dir.join(DICTIONARY_FILE))?;
+    let new_characters = parse_lines::<DictionaryEntry>(std::io::BufReader::new(file))?
+        .into_iter()
+        .map(|entry| persistence::model::NewCharacter {
+            stroke_count: None,
+            character: entry.character,
+            definition: entry.definition.unwrap_or_default(),
+            pinyin: entry.pinyin.join(", "),
+            decomposition: entry.decomposition,
+            radical: entry.radical,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(persistence::write::characters(database_url, &new_characters)?)
+}
+
+pub(crate) fn import_strokes(database_url: &str, data_dir: &Path) -> Result<usize, CharacterError> {
+    let file = std::fs::File::open(data_dir.join(GRAPHICS_FILE))?;
+    let new_strokes = parse_lines::<GraphicsEntry>(std::io::BufReader::new(file))?
+        .into_iter()
+        .flat_map(|entry| {
+            let character = entry.character;
+            entry
+                .strokes
+                .into_iter()
+                .zip(entry.medians)
+                .enumerate()
+                .map(move |(position, (path, median))| persistence::model::NewStroke {
+                    character: character.clone(),
+                    position: position as i32,
+                    path,
+                    median: serde_json::to_string(&median).unwrap_or_default(),
+                })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(persistence::write::strokes(database_url, &new_strokes)?)
+}
+
+pub(crate) fn load(database_url: &str, character: &str) -> Result<Option<model::Character>, CharacterError> {
+    let Some(stored) = persistence::read::character(database_url, character)? else {
+        return Ok(None);
+    };
+    let related = persistence::read::related_characters(database_url, character, &stored.radical, RELATED_LIMIT)?;
+    let strokes = persistence::read::strokes(database_url, character)?
+        .into_iter()
+        .map(|stroke| model::Stroke {
+            outline: parse_path(&stroke.path),
+            median: serde_json::from_str(&stroke.median)
+                .inspect_err(|err| log::error!("Invalid stroke median of {}: {}", character, err))
+                .unwrap_or_default(),
+        })
+        .collect();
+    Ok(Some(model::Character {
+        components: components(&stored.decomposition),
+        related,
+        strokes,
+        ..model::Character::from(stored)
+    }))
+}
+
+fn components(decomposition: &str) -> Vec<String> {
+    decomposition
+        .chars()
+        .filter(|c| cjk::is_cjk(*c))
+        .map(String::from)
+        .collect()
+}
+
+pub(crate) fn parse_path(path: &str) -> Vec<[f32; 2]> {
+    let spaced = path
+        .chars()
+        .flat_map(|c| {
+            if c.is_ascii_alphabetic() {
+                vec![' ', c, ' ']
+            } else {
+                vec![c]
+            }
+        })
+        .collect::<String>();
+    let mut tokens = spaced.split_whitespace().peekable();
+    let mut points = Vec::new();
+    let mut current = [0., 0.];
+    let mut command = "M";
+
+    while let Some(token) = tokens.peek() {
+        if token.chars().all(|c| c.is_ascii_alphabetic()) {
+            command = tokens.next().unwrap_or_default();
+            if command.eq_ignore_ascii_case("Z") {
+                continue;
+            }
+        }
+        let mut next_point = || -> Option<[f32; 2]> {
+            let x = tokens.next()?.parse().ok()?;
+            let y = tokens.next()?.parse().ok()?;
+            Some([x, y])
+        };
+        let segment = match command {
+            "M" | "L" => next_point().map(|point| vec![point]),
+            "Q" => next_point().zip(next_point()).map(|(control, end)| {
+                (1..=CURVE_SEGMENTS)
+                    .map(|step| {
+                        let t = step as f32 / CURVE_SEGMENTS as f32;
+                        let u = 1. - t;
+                        [
+                            u * u * current[0] + 2. * u * t * control[0] + t * t * end[0],
+                            u * u * current[1] + 2. * u * t * control[1] + t * t * end[1],
+                        ]
+                    })
+                    .collect()
+            }),
+            "C" => next_point()
+                .zip(next_point())
+                .zip(next_point())
+                .map(|((control_1, control_2), end)| {
+                    (1..=CURVE_SEGMENTS)
+                        .map(|step| {
+                            let t = step as f32 / CURVE_SEGMENTS as f32;
+                            let u = 1. - t;
+                            [
+                                u * u * u * current[0]
+                                    + 3. * u * u * t * control_1[0]
+                                    + 3. * u * t * t * control_2[0]
+                                    + t * t * t * end[0],
+                                u * u * u * current[1]
+                                    + 3. * u * u * t * control_1[1]
+                                    + 3. * u * t * t * control_2[1]
+                                    + t * t * t * end[1],
+                            ]
+                        })
+                        .collect()
+                }),
+            other => {
+                log::warn!("Unsupported path command {}", other);
+                None
+            }
+        };
+        match segment {
+            Some(segment) => {
+                current = segment.last().copied().unwrap_or(current);
+                points.extend(segment);
+            }
+            None => break,
+        }
+    }
+    points
+}
+
+fn parse_lines<T: serde::de::DeserializeOwned>(reader: impl BufRead) -> Result<Vec<T>, CharacterError> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            let line = line?;
+            serde_json::from_str::<T>(&line).map_err(|err| CharacterError::Parse(index + 1, err))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICTIONARY: &str = include_str!("../tests/fixtures/dictionary.txt");
+    const GRAPHICS: &str = include_str!("../tests/fixtures/graphics.txt");
+
+    #[test]
+    fn parses_the_entries_of_dictionary_fixture() {
+        let entries = parse_lines::<DictionaryEntry>(DICTIONARY.as_bytes()).unwrap();
+        let parsed = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.character.as_str(),
+                    entry.pinyin.join(", "),
+                    components(&entry.decomposition),
+                    entry.radical.as_str(),
+                    entry.definition.is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parsed,
+            vec![
+                ("一", "yī".to_string(), vec![], "一", true),
+                (
+                    "好",
+                    "hǎo, hào".to_string(),
+                    vec!["女".to_string(), "子".to_string()],
+                    "女",
+                    true
+                ),
+                (
+                    "妈",
+                    "mā".to_string(),
+                    vec!["女".to_string(), "马".to_string()],
+                    "女",
+                    true
+                ),
+                ("⺈", String::new(), vec![], "⺈", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_the_strokes_of_graphics_fixture() {
+        let entries = parse_lines::<GraphicsEntry>(GRAPHICS.as_bytes()).unwrap();
+        let point_counts = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.character.as_str(),
+                    entry
+                        .strokes
+                        .iter()
+                        .map(|path| parse_path(path).len())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            point_counts,
+            vec![("一", vec![18]), ("十", vec![4, 18]), ("人", vec![18, 18])]
+        );
+        assert!(entries.iter().all(|entry| entry.strokes.len() == entry.medians.len()));
+    }
+
+    #[test]
+    fn curves_end_at_their_end_point() {
+        let points = parse_path("M 0 0 Q 50 100 100 0 C 120 50 180 50 200 0");
+        assert_eq!(points.len(), 1 + 2 * CURVE_SEGMENTS);
+        assert_eq!(points[CURVE_SEGMENTS], [100., 0.]);
+        assert_eq!(points.last(), Some(&[200., 0.]));
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::cjk;
+use crate::model;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_TEXT_LENGTH: usize = 30;
+
+pub(crate) struct Watcher {
+    texts: mpsc::Receiver<String>,
+    running: Arc<AtomicBool>,
+}
+
+impl Watcher {
+    pub(crate) fn start(ctx: egui::Context) -> Self {
+        let (sender, texts) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let watching = running.clone();
+        std::thread::spawn(move || {
+            let mut clipboard = match arboard::Clipboard::new() {
+                Ok(clipboard) => clipboard,
+                Err(err) => {
+                    log::error!("Failed to access the clipboard: {}", err);
+                    watching.store(false, Ordering::Relaxed);
+                    ctx.request_repaint();
+                    return;
+                }
+            };
+            let mut last = clipboard.get_text().unwrap_or_default();
+            while watching.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL_INTERVAL);
+                let Ok(text) = clipboard.get_text() else {
+                    continue;
+                };
+                if text == last {
+                    continue;
+                }
+                last = text.clone();
+                if cjk::contains_cjk(&text) {
+                    if sender.send(text).is_err() {
+                        return;
+                    }
+                    ctx.request_repaint();
+                }
+            }
+        });
+        Self { texts, running }
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn copied(&self) -> impl Iterator<Item = String> + '_ {
+        self.texts.try_iter()
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn candidate(text: &str, settings: &model::ClipboardSettings) -> Option<String> {
+    let text = text.trim();
+    if !cjk::contains_cjk(text) || text.chars().count() > MAX_TEXT_LENGTH {
+        log::debug!("Ignoring copied text {:?}", text);
+        return None;
+    }
+    (!settings.is_ignored(text)).then(|| text.to_owned())
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::model;
+use crate::pinyin;
+
+const CEDICT_FILE: &str = "cedict_ts.u8";
+const CEDICT_LANGUAGE: &str = "English";
+const CLASSIFIER_PREFIX: &str = "CL:";
+
+pub(crate) const SOURCE: &str = "CC-CEDICT";
+
+struct Entry {
+    pinyin: String,
+    definitions: Vec<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct Dictionary {
+    entries: Vec<Entry>,
+    index: HashMap<String, Vec<usize>>,
+    max_length: usize,
+}
+
+impl Dictionary {
+    pub(crate) fn load(database_url: &str) -> Self {
+        let Some(data_dir) = Path::new(database_url).parent() else {
+            return Dictionary::default();
+        };
+        let file = data_dir.join(CEDICT_FILE);
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                log::info!(
+                    "No dictionary found, place CC-CEDICT as {} to look up words without the LLM: {}",
+                    file.display(),
+                    err
+                );
+                return Dictionary::default();
+            }
+        };
+        let mut dictionary = Dictionary::default();
+        content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(parse_line)
+            .for_each(|(traditional, simplified, entry)| {
+                let position = dictionary.entries.len();
+                dictionary.max_length = dictionary.max_length.max(traditional.chars().count());
+                dictionary.entries.push(entry);
+                dictionary
+                    .index
+                    .entry(traditional.to_owned())
+                    .or_default()
+                    .push(position);
+                if simplified != traditional {
+                    dictionary
+                        .index
+                        .entry(simplified.to_owned())
+                        .or_default()
+                        .push(position);
+                }
+            });
+        log::info!(
+            "Loaded {} dictionary entries from {}",
+            dictionary.entries.len(),
+            file.display()
+        );
+        dictionary
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn contains(&self, word: &str) -> bool {
+        self.index.contains_key(word)
+    }
+
+    pub(crate) fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    pub(crate) fn phrase(&self, text: &str) -> Option<model::Phrase> {
+        let entries = self
+            .index
+            .get(text.trim())?
+            .iter()
+            .map(|position| &self.entries[*position])
+            .collect::<Vec<_>>();
+        let first = entries.first()?;
+        let senses = entries
+            .iter()
+            .flat_map(|entry| {
+                let note = if entry.pinyin == first.pinyin {
+                    String::new()
+                } else {
+                    pinyin::marked(&entry.pinyin)
+                };
+                entry
+                    .definitions
+                    .iter()
+                    .filter(|definition| !definition.starts_with(CLASSIFIER_PREFIX))
+                    .map(move |definition| model::Sense {
+                        translation: definition.clone(),
+                        note: note.clone(),
+                    })
+            })
+            .collect::<Vec<_>>();
+        let classifiers = entries
+            .iter()
+            .flat_map(|entry| entry.definitions.iter())
+            .filter(|definition| definition.starts_with(CLASSIFIER_PREFIX))
+            .cloned()
+            .collect::<Vec<_>>();
+        let translation = senses
+            .first()
+            .map(|sense| sense.translation.clone())
+            .or_else(|| first.definitions.first().cloned())
+            .unwrap_or_default();
+        let senses = senses
+            .into_iter()
+            .chain(classifiers.into_iter().map(|classifier| model::Sense {
+                translation: classifier,
+                note: "measure word".to_string(),
+            }))
+            .collect();
+        Some(model::Phrase {
+            original: text.trim().to_owned(),
+            pinyin: pinyin::marked(&first.pinyin),
+            language: CEDICT_LANGUAGE.to_owned(),
+            translation,
+            senses,
+            examples: Vec::new(),
+            document_line: None,
+        })
+    }
+}
+
+fn parse_line(line: &str) -> Option<(&str, &str, Entry)> {
+    let (traditional, rest) = line.split_once(' ')?;
+    let (simplified, rest) = rest.split_once(' ')?;
+    let (pinyin, rest) = rest.strip_prefix('[')?.split_once(']')?;
+    let definitions = rest
+        .trim()
+        .trim_matches('/')
+        .split('/')
+        .filter(|definition| !definition.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    Some((
+        traditional,
+        simplified,
+        Entry {
+            pinyin: pinyin.to_owned(),
+            definitions,
+        },
+    ))
+}
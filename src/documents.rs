@@ -0,0 +1,471 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use crate::cjk;
+use crate::model;
+use crate::persistence;
+
+const CONTAINER_FILE: &str = "META-INF/container.xml";
+const BLOCK_TAGS: [&str; 14] = [
+    "p",
+    "div",
+    "br",
+    "li",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "section",
+    "hr",
+];
+const SKIPPED_TAGS: [&str; 3] = ["head", "script", "style"];
+const ASS_FORMAT: [&str; 10] = [
+    "Layer", "Start", "End", "Style", "Name", "MarginL", "MarginR", "MarginV", "Effect", "Text",
+];
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum DocumentError {
+    #[error("Document could not be read: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("EPUB could not be unpacked: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("EPUB metadata could not be parsed: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("Invalid EPUB: {0}")]
+    InvalidEpub(String),
+    #[error("Unsupported file type: {0}")]
+    UnsupportedFormat(String),
+    #[error("{0} is not UTF-8, UTF-16 or GB18030 text")]
+    Encoding(String),
+    #[error("No Chinese text found in {0}")]
+    NoChineseText(String),
+    #[error("Document could not be stored: {0}")]
+    Persistence(#[from] persistence::PersistenceError),
+}
+
+pub(crate) fn import(database_url: &str, path: &str) -> Result<i32, DocumentError> {
+    let file = Path::new(path);
+    let fingerprint = fingerprint(file)?;
+    match persistence::read::document(database_url, path)? {
+        Some(document) if document.fingerprint == fingerprint => {
+            log::info!("{} is already imported", path);
+            return Ok(document.id);
+        }
+        Some(_) => log::info!("{} has changed since it was imported, importing it again", path),
+        None => {}
+    }
+    let format = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(model::DocumentFormat::from_extension)
+        .ok_or_else(|| DocumentError::UnsupportedFormat(path.to_owned()))?;
+    let file_title = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or(path.to_owned());
+    let (title, lines) = match format {
+        model::DocumentFormat::Text => (file_title, text_lines(&read_text(file)?)),
+        model::DocumentFormat::Srt => (file_title, srt_lines(&read_text(file)?)),
+        model::DocumentFormat::Ass => (file_title, ass_lines(&read_text(file)?)),
+        model::DocumentFormat::Epub => epub_lines(file, file_title)?,
+    };
+    if lines.is_empty() {
+        return Err(DocumentError::NoChineseText(path.to_owned()));
+    }
+    let document_id =
+        persistence::write::document(database_url, &title, path, &format.to_string(), &fingerprint, &lines)?;
+    log::info!("Imported {} lines from {}", lines.len(), path);
+    Ok(document_id)
+}
+
+/// Size and modification time, enough to notice that a file was replaced or edited.
+fn fingerprint(file: &Path) -> Result<String, DocumentError> {
+    let metadata = std::fs::metadata(file)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(format!("{}:{}", metadata.len(), modified.as_nanos()))
+}
+
+fn read_text(file: &Path) -> Result<String, DocumentError> {
+    let bytes = std::fs::read(file)?;
+    let content = decode(&bytes).ok_or_else(|| DocumentError::Encoding(file.display().to_string()))?;
+    Ok(content.replace("\r\n", "\n"))
+}
+
+/// Decodes by byte order mark, otherwise as UTF-8 with a fallback to GB18030, a superset of GBK and GB2312.
+fn decode(bytes: &[u8]) -> Option<String> {
+    let decoded = match encoding_rs::Encoding::for_bom(bytes) {
+        Some((encoding, bom_length)) => {
+            encoding.decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])
+        }
+        None => std::str::from_utf8(bytes)
+            .ok()
+            .map(Cow::Borrowed)
+            .or_else(|| encoding_rs::GB18030.decode_without_bom_handling_and_without_replacement(bytes)),
+    };
+    decoded.map(Cow::into_owned)
+}
+
+fn text_lines(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| cjk::contains_cjk(line))
+        .map(|(index, line)| (format!("line {}", index + 1), line.to_owned()))
+        .collect()
+}
+
+fn srt_lines(content: &str) -> Vec<(String, String)> {
+    content
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let start = lines.next()?.split("-->").next()?.trim().to_owned();
+            let text = lines
+                .map(strip_markup)
+                .filter(|line| cjk::contains_cjk(line))
+                .collect::<Vec<_>>()
+                .join(" ");
+            (!text.is_empty()).then_some((start, text))
+        })
+        .collect()
+}
+
+fn ass_lines(content: &str) -> Vec<(String, String)> {
+    let mut events = false;
+    let mut format = ASS_FORMAT.map(str::to_owned).to_vec();
+    content
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            if line.starts_with('[') {
+                events = line.eq_ignore_ascii_case("[events]");
+                return None;
+            }
+            if !events {
+                return None;
+            }
+            if let Some(fields) = line.strip_prefix("Format:") {
+                format = fields.split(',').map(|field| field.trim().to_owned()).collect();
+                return None;
+            }
+            let values = line
+                .strip_prefix("Dialogue:")?
+                .splitn(format.len(), ',')
+                .collect::<Vec<_>>();
+            let value = |name: &str| {
+                format
+                    .iter()
+                    .position(|field| field == name)
+                    .and_then(|position| values.get(position))
+                    .map(|value| value.trim())
+            };
+            let text = strip_markup(
+                &value("Text")?
+                    .replace("\\N", " ")
+                    .replace("\\n", " ")
+                    .replace("\\h", " "),
+            );
+            cjk::contains_cjk(&text).then(|| (value("Start").unwrap_or_default().to_owned(), text))
+        })
+        .collect()
+}
+
+fn epub_lines(file: &Path, file_title: String) -> Result<(String, Vec<(String, String)>), DocumentError> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(file)?)?;
+
+    let container = read_entry(&mut archive, CONTAINER_FILE)?;
+    let container = roxmltree::Document::parse_with_options(&container, parsing_options())?;
+    let package_path = container
+        .descendants()
+        .find(|node| node.has_tag_name("rootfile"))
+        .and_then(|node| node.attribute("full-path"))
+        .ok_or_else(|| DocumentError::InvalidEpub("the package document is missing".to_string()))?
+        .to_owned();
+
+    let package = read_entry(&mut archive, &package_path)?;
+    let package = roxmltree::Document::parse_with_options(&package, parsing_options())?;
+    let title = package
+        .descendants()
+        .find(|node| node.tag_name().name() == "title")
+        .and_then(|node| node.text())
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_owned)
+        .unwrap_or(file_title);
+    let manifest = package
+        .descendants()
+        .filter(|node| node.tag_name().name() == "item")
+        .filter_map(|node| Some((node.attribute("id")?, node.attribute("href")?)))
+        .collect::<HashMap<_, _>>();
+    let base = package_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or_default();
+    let chapters = package
+        .descendants()
+        .filter(|node| node.tag_name().name() == "itemref")
+        .filter_map(|node| node.attribute("idref"))
+        .filter_map(|id| manifest.get(id))
+        .map(|href| resolve(base, &percent_decode(href)))
+        .collect::<Vec<_>>();
+
+    let lines = chapters
+        .iter()
+        .filter_map(|chapter| {
+            read_entry(&mut archive, chapter)
+                .inspect_err(|err| log::warn!("Skipping chapter {}: {}", chapter, err))
+                .ok()
+        })
+        .map(|html| {
+            html_paragraphs(&html)
+                .into_iter()
+                .filter(|paragraph| cjk::contains_cjk(paragraph))
+                .collect::<Vec<_>>()
+        })
+        .filter(|paragraphs| !paragraphs.is_empty())
+        .enumerate()
+        .flat_map(|(chapter, paragraphs)| {
+            paragraphs
+                .into_iter()
+                .enumerate()
+                .map(move |(paragraph, text)| (format!("chapter {}, paragraph {}", chapter + 1, paragraph + 1), text))
+        })
+        .collect();
+    Ok((title, lines))
+}
+
+fn parsing_options<'input>() -> roxmltree::ParsingOptions<'input> {
+    roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..roxmltree::ParsingOptions::default()
+    }
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<String, DocumentError> {
+    let mut content = String::new();
+    archive.by_name(name)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn resolve(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let mut parts = base.split('/').filter(|part| !part.is_empty()).collect::<Vec<_>>();
+    href.split('/').for_each(|part| match part {
+        "" | "." => {}
+        ".." => {
+            parts.pop();
+        }
+        part => parts.push(part),
+    });
+    parts.join("/")
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn html_paragraphs(html: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut text = String::new();
+    let mut skipped_depth = 0usize;
+    let mut flush = |text: &mut String| {
+        let paragraph = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !paragraph.is_empty() {
+            paragraphs.push(paragraph);
+        }
+        text.clear();
+    };
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if skipped_depth == 0 {
+            text.push_str(&decode_entities(&rest[..start]));
+        }
+        let Some(length) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[start + 1..start + length];
+        rest = &rest[start + length + 1..];
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if SKIPPED_TAGS.contains(&name.as_str()) {
+            if tag.starts_with('/') {
+                skipped_depth = skipped_depth.saturating_sub(1);
+            } else if !tag.ends_with('/') {
+                skipped_depth += 1;
+            }
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            flush(&mut text);
+        }
+    }
+    if skipped_depth == 0 {
+        text.push_str(&decode_entities(rest));
+    }
+    flush(&mut text);
+    paragraphs
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        "ldquo" => Some('“'),
+        "rdquo" => Some('”'),
+        "lsquo" => Some('‘'),
+        "rsquo" => Some('’'),
+        "hellip" => Some('…'),
+        "mdash" => Some('—'),
+        "ndash" => Some('–'),
+        "middot" => Some('·'),
+        _ => entity
+            .strip_prefix("#x")
+            .or_else(|| entity.strip_prefix("#X"))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .or_else(|| entity.strip_prefix('#').and_then(|decimal| decimal.parse().ok()))
+            .and_then(char::from_u32),
+    }
+}
+
+fn strip_markup(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut closing = None;
+    text.chars().for_each(|c| match (closing, c) {
+        (None, '<') => closing = Some('>'),
+        (None, '{') => closing = Some('}'),
+        (None, c) => stripped.push(c),
+        (Some(end), c) if c == end => closing = None,
+        _ => {}
+    });
+    stripped.trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(reference, text)| (reference.to_string(), text.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_the_chinese_cues_of_srt() {
+        let content = "1\n00:00:01,000 --> 00:00:02,500\n<i>你好</i>\n\n2\n00:00:02,600 --> 00:00:03,000\nHello\n\n3\n00:00:03,000 --> 00:00:04,000\n世界\n再见\n";
+        assert_eq!(
+            srt_lines(content),
+            lines(&[("00:00:01,000", "你好"), ("00:00:03,000", "世界 再见")])
+        );
+    }
+
+    #[test]
+    fn reads_the_dialogue_of_ass_events() {
+        let content = "[Script Info]\nTitle: 测试\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\i1}你好，世界\\N再见\nDialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Hello\nComment: 0,0:00:05.00,0:00:06.00,Default,,0,0,0,,注释\n";
+        assert_eq!(ass_lines(content), lines(&[("0:00:01.00", "你好，世界 再见")]));
+    }
+
+    #[test]
+    fn follows_the_format_line_of_ass_events() {
+        let content = "[Events]\nFormat: Start, Text\nDialogue: 0:00:01.00,你好, 世界\n";
+        assert_eq!(ass_lines(content), lines(&[("0:00:01.00", "你好, 世界")]));
+    }
+
+    #[test]
+    fn splits_html_into_paragraphs() {
+        let html = "<html><head><title>标题</title><style>p { color: red; }</style></head>\
+            <body><h1>第一章</h1><p>他说：&ldquo;你好&rdquo;&hellip;</p><p>A &amp; B&#12290;<br/>&#x4E16;界</p></body></html>";
+        assert_eq!(
+            html_paragraphs(html),
+            vec!["第一章", "他说：“你好”…", "A & B。", "世界"]
+        );
+    }
+
+    #[test]
+    fn skips_nested_and_sequential_skipped_elements() {
+        let html = "<html><head><style>p { color: red; }</style><title>标题</title>\
+            <script>var a = '<p>脚本</p>';</script></head><body><p>正文</p><script/><p>结尾</p></body></html>";
+        assert_eq!(html_paragraphs(html), vec!["正文", "结尾"]);
+    }
+
+    #[test]
+    fn keeps_unknown_entities() {
+        assert_eq!(decode_entities("&unknown; &amp &lt;"), "&unknown; &amp <");
+    }
+
+    #[test]
+    fn resolves_hrefs_against_the_package_directory() {
+        assert_eq!(resolve("OEBPS", "../Text/ch%201.xhtml#x"), "Text/ch%201.xhtml");
+        assert_eq!(resolve("OEBPS/", "./Text/chapter.xhtml"), "OEBPS/Text/chapter.xhtml");
+        assert_eq!(resolve("", "chapter.xhtml"), "chapter.xhtml");
+        assert_eq!(percent_decode("ch%201.xhtml"), "ch 1.xhtml");
+    }
+
+    #[test]
+    fn decodes_gb18030_and_utf16() {
+        let (gbk, _, _) = encoding_rs::GBK.encode("你好，世界");
+        assert_eq!(decode(&gbk).as_deref(), Some("你好，世界"));
+        assert_eq!(decode(b"\xff\xfe\x60\x4f\x7d\x59").as_deref(), Some("你好"));
+        assert_eq!(decode("\u{feff}你好".as_bytes()).as_deref(), Some("你好"));
+        assert_eq!(decode(b"\xff\xff"), None);
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::model;
+use crate::persistence;
+use crate::pinyin;
+
+const CONFUSED_PAIRS: usize = 5;
+
+pub(crate) fn check(expected: &str, answer: &str) -> Vec<model::SyllableCheck> {
+    let answered = pinyin::syllables(answer);
+    pinyin::syllables(expected)
+        .into_iter()
+        .enumerate()
+        .map(|(index, syllable)| model::SyllableCheck {
+            expected: pinyin::plain(&expected[syllable.range]),
+            expected_tone: syllable.tone,
+            answered: answered
+                .get(index)
+                .map(|given| (pinyin::plain(&answer[given.range.clone()]), given.tone)),
+        })
+        .collect()
+}
+
+pub(crate) fn answers(item: &str, checks: &[model::SyllableCheck]) -> Vec<persistence::model::NewToneAnswer> {
+    checks
+        .iter()
+        .enumerate()
+        .map(|(index, check)| {
+            let previous = index.checked_sub(1).and_then(|previous| checks.get(previous));
+            persistence::model::NewToneAnswer {
+                item: item.to_owned(),
+                expected: check.expected_tone.number(),
+                answered: check.answered_tone().map(|tone| tone.number()),
+                previous_expected: previous.map(|previous| previous.expected_tone.number()),
+                previous_answered: previous
+                    .and_then(|previous| previous.answered_tone())
+                    .map(|tone| tone.number()),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn statistics(answers: &[persistence::model::ToneAnswer]) -> model::ToneStatistics {
+    let tone = |number: Option<i32>| number.and_then(model::Tone::from_number);
+
+    let tones = model::Tone::ALL
+        .into_iter()
+        .map(|expected| {
+            let answered = answers
+                .iter()
+                .filter(|answer| tone(Some(answer.expected)) == Some(expected))
+                .collect::<Vec<_>>();
+            let errors = answered
+                .iter()
+                .filter(|answer| tone(answer.answered) != Some(expected))
+                .count();
+            (expected, answered.len(), errors)
+        })
+        .filter(|(_, total, _)| *total > 0)
+        .collect();
+
+    let mut pairs = HashMap::new();
+    answers
+        .iter()
+        .filter(|answer| {
+            answer.previous_expected.is_some()
+                && (Some(answer.expected) != answer.answered || answer.previous_expected != answer.previous_answered)
+        })
+        .filter_map(|answer| {
+            Some((
+                (tone(answer.previous_expected)?, tone(Some(answer.expected))?),
+                (tone(answer.previous_answered), tone(answer.answered)),
+            ))
+        })
+        .for_each(|pair| *pairs.entry(pair).or_insert(0) += 1);
+    let mut confused_pairs = pairs
+        .into_iter()
+        .map(|((expected, answered), count)| model::ConfusedPair {
+            expected,
+            answered,
+            count,
+        })
+        .collect::<Vec<_>>();
+    confused_pairs.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(a.expected.0.index().cmp(&b.expected.0.index()))
+            .then(a.expected.1.index().cmp(&b.expected.1.index()))
+    });
+    confused_pairs.truncate(CONFUSED_PAIRS);
+
+    model::ToneStatistics { tones, confused_pairs }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use crate::model;
+use crate::script;
+
+pub(crate) const CLOZE_GAP: &str = "＿＿";
+
+const CHOICES: usize = 4;
+const INITIAL_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+
+pub(crate) fn cards(
+    kind: model::ExerciseKind,
+    phrases: Vec<model::Phrase>,
+    saved_phrases: &[model::Phrase],
+    due: &HashMap<String, bool>,
+    size: usize,
+) -> Vec<model::Card> {
+    let mut phrases = phrases
+        .into_iter()
+        .filter(|phrase| due.get(&phrase.original).copied().unwrap_or(true))
+        .collect::<Vec<_>>();
+    phrases.sort_by_key(|phrase| !due.contains_key(&phrase.original));
+    phrases
+        .into_iter()
+        .filter_map(|phrase| card(kind, phrase, saved_phrases))
+        .take(size)
+        .collect()
+}
+
+pub(crate) fn is_correct(converter: &script::Converter, expected: &str, answer: &str) -> bool {
+    let normalize = |text: &str| {
+        converter
+            .convert(text, model::Script::Simplified)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+    };
+    !answer.trim().is_empty() && normalize(expected) == normalize(answer)
+}
+
+pub(crate) fn reschedule(schedule: Option<model::Schedule>, correct: bool) -> model::Schedule {
+    let schedule = schedule.unwrap_or(model::Schedule {
+        repetitions: 0,
+        interval_days: 0,
+        ease: INITIAL_EASE,
+    });
+    if !correct {
+        return model::Schedule {
+            repetitions: 0,
+            interval_days: 1,
+            ease: (schedule.ease - 0.2).max(MIN_EASE),
+        };
+    }
+    let interval_days = match schedule.repetitions {
+        0 => 1,
+        1 => 6,
+        _ => (schedule.interval_days as f32 * schedule.ease).round() as i32,
+    };
+    model::Schedule {
+        repetitions: schedule.repetitions + 1,
+        interval_days,
+        ease: schedule.ease + 0.1,
+    }
+}
+
+fn card(kind: model::ExerciseKind, phrase: model::Phrase, saved_phrases: &[model::Phrase]) -> Option<model::Card> {
+    match kind {
+        model::ExerciseKind::Recognition | model::ExerciseKind::Recall => Some(model::Card {
+            phrase,
+            choices: Vec::new(),
+            cloze: None,
+        }),
+        model::ExerciseKind::Listening => {
+            let choices = choices(&phrase, saved_phrases);
+            (choices.len() > 1).then_some(model::Card {
+                phrase,
+                choices,
+                cloze: None,
+            })
+        }
+        model::ExerciseKind::Cloze => {
+            let cloze = phrase
+                .examples
+                .iter()
+                .find(|example| example.hanzi.contains(&phrase.original))
+                .map(|example| model::Example {
+                    hanzi: example.hanzi.replacen(&phrase.original, CLOZE_GAP, 1),
+                    pinyin: example.pinyin.clone(),
+                    translation: example.translation.clone(),
+                })?;
+            Some(model::Card {
+                phrase,
+                choices: Vec::new(),
+                cloze: Some(cloze),
+            })
+        }
+        model::ExerciseKind::ToneDrill | model::ExerciseKind::Handwriting => None,
+    }
+}
+
+fn choices(phrase: &model::Phrase, saved_phrases: &[model::Phrase]) -> Vec<String> {
+    let length = phrase.original.chars().count();
+    let mut distractors = saved_phrases
+        .iter()
+        .map(|saved| saved.original.clone())
+        .filter(|original| *original != phrase.original)
+        .collect::<Vec<_>>();
+    distractors.sort_by_key(|original| {
+        (
+            original.chars().count().abs_diff(length),
+            shuffle_key(&phrase.original, original),
+        )
+    });
+    distractors.dedup();
+    let mut choices = distractors.into_iter().take(CHOICES - 1).collect::<Vec<_>>();
+    let position = shuffle_key(&phrase.original, "") as usize % (choices.len() + 1);
+    choices.insert(position, phrase.original.clone());
+    choices
+}
+
+fn shuffle_key(seed: &str, text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    text.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use crate::model;
+use crate::persistence;
+
+const STREAK_WINDOW_DAYS: i32 = 32;
+
+pub(crate) fn progress(
+    database_url: &str,
+    goals: &model::DailyGoals,
+) -> Result<model::GoalProgress, persistence::PersistenceError> {
+    let mut window = STREAK_WINDOW_DAYS;
+    loop {
+        let days = activity(database_url, window)?;
+        let day = |days_ago| {
+            let (new_phrases, reviews, study_ms) = days.get(&days_ago).copied().unwrap_or_default();
+            model::GoalProgress {
+                new_phrases,
+                reviews,
+                minutes: (study_ms / 60_000) as u32,
+                streak: 0,
+            }
+        };
+        let is_met = |days_ago| days.contains_key(&days_ago) && goals.is_met(&day(days_ago));
+
+        let first = if is_met(0) { 0 } else { 1 };
+        let streak = (first..window).take_while(|days_ago| is_met(*days_ago)).count() as i32;
+        // A streak reaching the start of the window may go back further
+        if first + streak < window {
+            return Ok(model::GoalProgress {
+                streak: streak as u32,
+                ..day(0)
+            });
+        }
+        window *= 2;
+    }
+}
+
+fn activity(database_url: &str, window: i32) -> Result<HashMap<i32, (u32, u32, i64)>, persistence::PersistenceError> {
+    let mut days = HashMap::<i32, (u32, u32, i64)>::new();
+    persistence::read::activity(database_url, window)?
+        .into_iter()
+        .for_each(|(kind, days_ago, duration_ms)| {
+            let (new_phrases, reviews, study_ms) = days.entry(days_ago).or_default();
+            if kind == model::EventKind::Save.to_string() {
+                *new_phrases += 1;
+            } else if kind == model::EventKind::Review.to_string() {
+                *reviews += 1;
+            } else if kind == model::EventKind::Study.to_string() {
+                *study_ms += duration_ms as i64;
+            }
+        });
+    Ok(days)
+}
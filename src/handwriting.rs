@@ -0,0 +1,64 @@
+use crate::model;
+
+const SAMPLE_POINTS: usize = 16;
+const MATCH_THRESHOLD: f32 = 140.;
+
+pub(crate) fn check_stroke(drawn: &[[f32; 2]], strokes: &[model::Stroke], expected: usize) -> model::StrokeFeedback {
+    let Some(reference) = strokes.get(expected) else {
+        return model::StrokeFeedback::Mismatch;
+    };
+    if drawn.len() < 2 {
+        return model::StrokeFeedback::Mismatch;
+    }
+    let drawn = resample(drawn);
+    if distance(&drawn, &resample(&reference.median)) < MATCH_THRESHOLD {
+        return model::StrokeFeedback::Correct;
+    }
+    let reversed = reference.median.iter().rev().copied().collect::<Vec<_>>();
+    if distance(&drawn, &resample(&reversed)) < MATCH_THRESHOLD {
+        return model::StrokeFeedback::WrongDirection;
+    }
+    strokes
+        .iter()
+        .enumerate()
+        .skip(expected + 1)
+        .find(|(_, stroke)| distance(&drawn, &resample(&stroke.median)) < MATCH_THRESHOLD)
+        .map(|(index, _)| model::StrokeFeedback::WrongOrder(index))
+        .unwrap_or(model::StrokeFeedback::Mismatch)
+}
+
+fn resample(points: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    let lengths = points
+        .windows(2)
+        .map(|pair| (pair[1][0] - pair[0][0]).hypot(pair[1][1] - pair[0][1]))
+        .collect::<Vec<_>>();
+    let total = lengths.iter().sum::<f32>();
+    let first = points.first().copied().unwrap_or_default();
+    if total == 0. {
+        return vec![first; SAMPLE_POINTS];
+    }
+    (0..SAMPLE_POINTS)
+        .map(|sample| {
+            let mut remaining = total * sample as f32 / (SAMPLE_POINTS - 1) as f32;
+            for (pair, length) in points.windows(2).zip(&lengths) {
+                if remaining <= *length && *length > 0. {
+                    let t = remaining / length;
+                    return [
+                        pair[0][0] + (pair[1][0] - pair[0][0]) * t,
+                        pair[0][1] + (pair[1][1] - pair[0][1]) * t,
+                    ];
+                }
+                remaining -= length;
+            }
+            points.last().copied().unwrap_or(first)
+        })
+        .collect()
+}
+
+fn distance(a: &[[f32; 2]], b: &[[f32; 2]]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a[0] - b[0]).hypot(a[1] - b[1]))
+        .sum::<f32>()
+        / a.len().max(1) as f32
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::cjk;
+use crate::model;
+use crate::persistence;
+use crate::script;
+
+const HSK_DIR: &str = "hsk";
+const HSK2_LISTS: [&str; 6] = [
+    include_str!("../assets/hsk/hsk2-1.txt"),
+    include_str!("../assets/hsk/hsk2-2.txt"),
+    include_str!("../assets/hsk/hsk2-3.txt"),
+    include_str!("../assets/hsk/hsk2-4.txt"),
+    include_str!("../assets/hsk/hsk2-5.txt"),
+    include_str!("../assets/hsk/hsk2-6.txt"),
+];
+const HSK3_LISTS: [&str; 7] = [
+    include_str!("../assets/hsk/hsk3-1.txt"),
+    include_str!("../assets/hsk/hsk3-2.txt"),
+    include_str!("../assets/hsk/hsk3-3.txt"),
+    include_str!("../assets/hsk/hsk3-4.txt"),
+    include_str!("../assets/hsk/hsk3-5.txt"),
+    include_str!("../assets/hsk/hsk3-6.txt"),
+    include_str!("../assets/hsk/hsk3-7.txt"),
+];
+
+#[derive(Default)]
+pub(crate) struct Index {
+    hsk2: HashMap<String, i32>,
+    hsk3: HashMap<String, i32>,
+    max_length: usize,
+}
+
+impl Index {
+    pub(crate) fn load(database_url: &str) -> Self {
+        let mut index = Index::default();
+        match persistence::read::hsk_words(database_url) {
+            Ok(words) => words.into_iter().for_each(|(word, version, level)| {
+                index.max_length = index.max_length.max(word.chars().count());
+                match version.parse() {
+                    Ok(model::HskVersion::Hsk2) => index.hsk2.insert(word, level),
+                    Ok(model::HskVersion::Hsk3) => index.hsk3.insert(word, level),
+                    Err(err) => {
+                        log::warn!("Ignoring HSK word {}: {}", word, err);
+                        None
+                    }
+                };
+            }),
+            Err(err) => log::error!("Failed to load HSK words: {}", err),
+        }
+        index
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hsk2.is_empty() && self.hsk3.is_empty()
+    }
+
+    pub(crate) fn level(&self, word: &str, version: model::HskVersion) -> Option<i32> {
+        match version {
+            model::HskVersion::Hsk2 => self.hsk2.get(word).copied(),
+            model::HskVersion::Hsk3 => self.hsk3.get(word).copied(),
+        }
+    }
+
+    pub(crate) fn words(&self, text: &str, version: model::HskVersion) -> Vec<(String, Option<i32>)> {
+        let chars = text.chars().filter(|c| cjk::is_cjk(*c)).collect::<Vec<_>>();
+        let mut words = Vec::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let longest = (1..=self.max_length.min(chars.len() - start)).rev().find_map(|length| {
+                let word = chars[start..start + length].iter().collect::<String>();
+                self.level(&word, version).map(|level| (word, length, level))
+            });
+            match longest {
+                Some((word, length, level)) => {
+                    words.push((word, Some(level)));
+                    start += length;
+                }
+                None => {
+                    words.push((chars[start].to_string(), None));
+                    start += 1;
+                }
+            }
+        }
+        words
+    }
+
+    pub(crate) fn phrase_level(&self, text: &str, version: model::HskVersion) -> Option<i32> {
+        self.level(text, version).or_else(|| {
+            self.words(text, version)
+                .into_iter()
+                .filter_map(|(_, level)| level)
+                .max()
+        })
+    }
+}
+
+pub(crate) fn dir(database_url: &str) -> Option<PathBuf> {
+    Path::new(database_url).parent().map(|data_dir| data_dir.join(HSK_DIR))
+}
+
+pub(crate) fn import_if_missing(database_url: &str) -> bool {
+    let dir = dir(database_url).filter(|dir| dir.exists());
+    let source = match &dir {
+        Some(dir) => dir.display().to_string(),
+        None => "the bundled word lists".to_string(),
+    };
+    let imported = match persistence::read::hsk_levels(database_url) {
+        Ok(levels) => levels,
+        Err(err) => {
+            log::error!("Failed to check for HSK words: {}", err);
+            return false;
+        }
+    };
+    let new_words = model::HskVersion::ALL
+        .into_iter()
+        .flat_map(|version| version.levels().map(move |level| (version, level)))
+        .filter(|(version, level)| !imported.contains(&(version.to_string(), *level)))
+        .flat_map(|(version, level)| {
+            let list = match &dir {
+                Some(dir) => {
+                    let file = dir.join(format!("hsk{}-{}.txt", version.major(), level));
+                    std::fs::read_to_string(&file)
+                        .inspect_err(|err| log::warn!("HSK word list {} not available: {}", file.display(), err))
+                        .unwrap_or_default()
+                }
+                None => bundled(version, level).to_string(),
+            };
+            list.lines()
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(|line| line.split_whitespace().next())
+                .flat_map(|words| words.split(['|', '｜', '/', '…']))
+                .filter(|word| cjk::contains_cjk(word))
+                .map(|word| persistence::model::NewHskWord {
+                    word: word.to_owned(),
+                    version: version.to_string(),
+                    level,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    if new_words.is_empty() {
+        return false;
+    }
+    match persistence::write::hsk_words(database_url, &new_words) {
+        Ok(count) => {
+            log::info!("Imported {} HSK words from {}", count, source);
+            count > 0
+        }
+        Err(err) => {
+            log::error!("Failed to import HSK words: {}", err);
+            false
+        }
+    }
+}
+
+/// The 2021 standard groups levels 7 to 9 into one band, its words are bundled as level 7.
+fn bundled(version: model::HskVersion, level: i32) -> &'static str {
+    let lists: &[&str] = match version {
+        model::HskVersion::Hsk2 => &HSK2_LISTS,
+        model::HskVersion::Hsk3 => &HSK3_LISTS,
+    };
+    usize::try_from(level - 1)
+        .ok()
+        .and_then(|index| lists.get(index))
+        .copied()
+        .unwrap_or_default()
+}
+
+pub(crate) fn levels(index: &Index, converter: &script::Converter, original: &str) -> (Option<i32>, Option<i32>) {
+    let simplified = converter.convert(original, model::Script::Simplified);
+    (
+        index.phrase_level(&simplified, model::HskVersion::Hsk2),
+        index.phrase_level(&simplified, model::HskVersion::Hsk3),
+    )
+}
+
+pub(crate) fn annotate(
+    database_url: &str,
+    index: &Index,
+    converter: &script::Converter,
+    original: &str,
+) -> Result<usize, persistence::PersistenceError> {
+    let (hsk2_level, hsk3_level) = levels(index, converter, original);
+    persistence::write::phrase_hsk_levels(database_url, original, hsk2_level, hsk3_level)
+}
+
+pub(crate) fn annotate_all(database_url: &str, index: &Index, converter: &script::Converter) {
+    match persistence::read::phrase_originals(database_url) {
+        Ok(originals) => originals.iter().for_each(|original| {
+            if let Err(err) = annotate(database_url, index, converter, original) {
+                log::error!("Failed to annotate phrase {} with HSK levels: {}", original, err);
+            }
+        }),
+        Err(err) => log::error!("Failed to load phrases for HSK levels: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_every_level_up_to_the_band() {
+        model::HskVersion::ALL.into_iter().for_each(|version| {
+            version.levels().filter(|level| *level <= 7).for_each(|level| {
+                assert!(
+                    bundled(version, level).lines().any(cjk::contains_cjk),
+                    "{} level {}",
+                    version,
+                    level
+                );
+            })
+        });
+        assert!(bundled(model::HskVersion::Hsk2, 1).lines().any(|word| word == "爱"));
+        assert_eq!(bundled(model::HskVersion::Hsk3, 8), "");
+    }
+}
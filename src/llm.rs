@@ -3,11 +3,68 @@ use ollama_rs::Ollama;
 use ollama_rs::error::OllamaError;
 use ollama_rs::generation::chat::ChatMessage;
 use ollama_rs::generation::chat::request::ChatMessageRequest;
+use ollama_rs::models::ModelOptions;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
+use crate::cjk;
 use crate::model;
 
+pub(crate) const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
 pub(crate) struct Query {
     pub(crate) text: String,
+    pub(crate) direction: model::Direction,
+    pub(crate) language: String,
+    pub(crate) templates: model::PromptTemplates,
+    pub(crate) options: model::GenerationOptions,
+    pub(crate) retry: Retry,
+}
+
+pub(crate) struct ExamplesQuery {
+    pub(crate) text: String,
+    pub(crate) language: String,
+    pub(crate) count: usize,
+    pub(crate) template: String,
+    pub(crate) options: model::GenerationOptions,
+    pub(crate) retry: Retry,
+}
+
+#[derive(Clone)]
+pub(crate) struct Retry {
+    pub(crate) max_attempts: u32,
+    attempt: Arc<AtomicU32>,
+}
+
+impl Retry {
+    pub(crate) fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            attempt: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    pub(crate) fn attempt(&self) -> u32 {
+        self.attempt.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) struct ValidationQuery {
+    pub(crate) kind: model::PromptKind,
+    pub(crate) language: String,
+    pub(crate) template: String,
+    pub(crate) options: model::GenerationOptions,
+}
+
+#[derive(Deserialize)]
+struct Examples {
+    examples: Vec<model::Example>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -22,6 +79,8 @@ pub(crate) enum LLMError {
     LocalModelNotFound,
     #[error("Environment variable not set")]
     EnvVar(#[from] std::env::VarError),
+    #[error("Invalid prompt template: {0}")]
+    InvalidTemplate(String),
 }
 
 impl LLMError {
@@ -38,6 +97,7 @@ impl LLMError {
             LLMError::InvalidJson(error) => error.to_string(),
             LLMError::LocalModelNotFound => "Local LLM model not found".to_string(),
             LLMError::EnvVar(error) => error.to_string(),
+            LLMError::InvalidTemplate(error) => error.to_string(),
         }
     }
 }
@@ -50,36 +110,226 @@ pub(crate) async fn list_local_model_names() -> Result<NEVec<String>, LLMError>
 }
 
 pub(crate) async fn query(llm_model: String, query: Query) -> Result<model::Phrase, LLMError> {
-    let prompt = get_prompt(&query.text);
+    let kind = match query.direction {
+        model::Direction::Detect if cjk::contains_cjk(&query.text) => model::PromptKind::Translate,
+        model::Direction::Detect => model::PromptKind::ReverseTranslate,
+        model::Direction::FromChinese => model::PromptKind::Translate,
+        model::Direction::IntoChinese => model::PromptKind::ReverseTranslate,
+    };
+    let prompt = render_prompt(kind, query.templates.get(kind), &query.text, &query.language, 0);
+
+    let mut response = query_json::<model::Phrase>(llm_model, &query.options, &query.retry, prompt).await?;
+    response.language = query.language;
+    Ok(response)
+}
 
-    log::debug!("Querying LLM model {} with prompt {}", llm_model, prompt);
+pub(crate) async fn examples(llm_model: String, query: ExamplesQuery) -> Result<Vec<model::Example>, LLMError> {
+    let prompt = render_prompt(
+        model::PromptKind::Examples,
+        &query.template,
+        &query.text,
+        &query.language,
+        query.count,
+    );
 
-    let llm_response = query_llm(llm_model, prompt).await?;
+    let response = query_json::<Examples>(llm_model, &query.options, &query.retry, prompt).await?;
+    Ok(response.examples)
+}
+
+pub(crate) async fn validate_template(llm_model: String, query: ValidationQuery) -> Result<(), LLMError> {
+    if !query.template.contains("{text}") {
+        return Err(LLMError::InvalidTemplate("placeholder {text} is missing".to_string()));
+    }
 
-    log::debug!("LLM response: {}", llm_response);
+    let sample = match query.kind {
+        model::PromptKind::Translate | model::PromptKind::Examples => "你好",
+        model::PromptKind::ReverseTranslate => "hello",
+    };
+    let prompt = render_prompt(query.kind, &query.template, sample, &query.language, 1);
+    let retry = Retry::new(1);
 
-    let json = extract_json_string(&llm_response)?;
-    let response = serde_json::from_str::<model::Phrase>(json)?;
-    Ok(response)
+    match query.kind {
+        model::PromptKind::Translate | model::PromptKind::ReverseTranslate => {
+            query_json::<model::Phrase>(llm_model, &query.options, &retry, prompt).await?;
+        }
+        model::PromptKind::Examples => {
+            query_json::<Examples>(llm_model, &query.options, &retry, prompt).await?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn default_templates() -> model::PromptTemplates {
+    model::PromptTemplates {
+        translate: default_template(model::PromptKind::Translate).to_owned(),
+        reverse_translate: default_template(model::PromptKind::ReverseTranslate).to_owned(),
+        examples: default_template(model::PromptKind::Examples).to_owned(),
+    }
+}
+
+pub(crate) fn default_template(kind: model::PromptKind) -> &'static str {
+    match kind {
+        model::PromptKind::Translate => {
+            r#"Translate the following Chinese phrase into {target_language} and return the result as {format}
+
+Chinese phrase: {text}"#
+        }
+        model::PromptKind::ReverseTranslate => {
+            r#"Translate the following {target_language} phrase into Chinese and return the result as {format}
+
+{target_language} phrase: {text}"#
+        }
+        model::PromptKind::Examples => {
+            r#"Write {count} short and natural example sentences in Chinese using the following Chinese phrase and return them as {format}
+
+Chinese phrase: {text}"#
+        }
+    }
+}
+
+fn get_format(kind: model::PromptKind) -> &'static str {
+    match kind {
+        model::PromptKind::Translate => {
+            r#"JSON containing the original text as 'original', it's Pinyin as 'pinyin', the most common translation as 'translation', and every distinct sense of the phrase as 'senses'.
+Each element of 'senses' is an object containing the {target_language} translation of this sense as 'translation' and a short note on its usage as 'note'."#
+        }
+        model::PromptKind::ReverseTranslate => {
+            r#"JSON containing the Chinese translation in Chinese characters as 'original', it's Pinyin as 'pinyin', the {target_language} phrase as 'translation', and every distinct sense of the Chinese translation as 'senses'.
+Each element of 'senses' is an object containing the {target_language} translation of this sense as 'translation' and a short note on its usage as 'note'."#
+        }
+        model::PromptKind::Examples => {
+            r#"JSON containing the list of sentences as 'examples'.
+Each element of 'examples' is an object containing the sentence in Chinese characters as 'hanzi', it's Pinyin as 'pinyin', and the {target_language} translation as 'translation'."#
+        }
+    }
+}
+
+fn render_prompt(kind: model::PromptKind, template: &str, text: &str, language: &str, count: usize) -> String {
+    template
+        .replace("{format}", get_format(kind))
+        .replace("{target_language}", language)
+        .replace("{count}", &count.to_string())
+        .replace("{text}", text)
+}
+
+fn get_repair_prompt(error: &str) -> String {
+    format!(
+        r#"
+Your previous answer could not be parsed as JSON: {error}
+Reply with the corrected JSON only, without any explanation."#
+    )
 }
 
-fn get_prompt(request: &str) -> String {
-    let prompt = r#"
-Translate the following Chinese phrase into English and return the result as JSON containing the original text as 'original', it's Pinyin as 'pinyin', and the translation as 'translation'.
+async fn query_json<T: DeserializeOwned>(
+    llm_model: String,
+    options: &model::GenerationOptions,
+    retry: &Retry,
+    prompt: String,
+) -> Result<T, LLMError> {
+    let mut messages = vec![ChatMessage::user(prompt)];
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        retry.attempt.store(attempt, Ordering::Relaxed);
+
+        log::debug!(
+            "Querying LLM model {} (attempt {} of {}) with messages {:?}",
+            llm_model,
+            attempt,
+            retry.max_attempts,
+            messages
+        );
+
+        let result = match query_llm(llm_model.clone(), options, messages.clone()).await {
+            Ok(llm_response) => {
+                log::debug!("LLM response: {}", llm_response);
+                parse_json::<T>(&llm_response).map_err(|err| (Some(llm_response), err))
+            }
+            Err(err) => Err((None, err)),
+        };
 
-Chinese phrase: "#;
+        match result {
+            Ok(response) => return Ok(response),
+            Err((_, err)) if attempt >= retry.max_attempts => {
+                log::error!(
+                    "Attempt {} of {} failed, giving up: {}",
+                    attempt,
+                    retry.max_attempts,
+                    err
+                );
+                return Err(err);
+            }
+            Err((Some(llm_response), err)) => {
+                log::warn!(
+                    "Attempt {} of {} returned invalid JSON, asking for a correction: {}",
+                    attempt,
+                    retry.max_attempts,
+                    err.cause()
+                );
+                messages.push(ChatMessage::assistant(llm_response));
+                messages.push(ChatMessage::user(get_repair_prompt(&err.cause())));
+            }
+            Err((None, err)) => {
+                log::warn!(
+                    "Attempt {} of {} failed, retrying in {:?}: {}",
+                    attempt,
+                    retry.max_attempts,
+                    backoff,
+                    err.cause()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+
+        attempt += 1;
+    }
+}
 
-    prompt.to_owned() + request
+fn parse_json<T: DeserializeOwned>(llm_response: &str) -> Result<T, LLMError> {
+    let json = extract_json_string(llm_response)?;
+    let response = serde_json::from_str::<T>(json)?;
+    Ok(response)
 }
 
-async fn query_llm(model_name: String, prompt: String) -> Result<String, LLMError> {
+async fn query_llm(
+    model_name: String,
+    options: &model::GenerationOptions,
+    messages: Vec<ChatMessage>,
+) -> Result<String, LLMError> {
+    let messages = if options.system_prompt.trim().is_empty() {
+        messages
+    } else {
+        std::iter::once(ChatMessage::system(options.system_prompt.clone()))
+            .chain(messages)
+            .collect()
+    };
+
     let llm_response = Ollama::default()
-        .send_chat_messages(ChatMessageRequest::new(model_name, vec![ChatMessage::user(prompt)]))
+        .send_chat_messages(ChatMessageRequest::new(model_name, messages).options(get_model_options(options)))
         .await
         .map(|res| res.message.content)?;
     Ok(llm_response)
 }
 
+fn get_model_options(options: &model::GenerationOptions) -> ModelOptions {
+    let mut model_options = ModelOptions::default();
+    if let Some(temperature) = options.temperature {
+        model_options = model_options.temperature(temperature);
+    }
+    if let Some(top_p) = options.top_p {
+        model_options = model_options.top_p(top_p);
+    }
+    if let Some(num_ctx) = options.num_ctx {
+        model_options = model_options.num_ctx(num_ctx as u64);
+    }
+    if let Some(seed) = options.seed {
+        model_options = model_options.seed(seed);
+    }
+    model_options
+}
+
 fn extract_json_string(s: &str) -> Result<&str, LLMError> {
     let start = s.find('{');
     let end = s.rfind('}');
@@ -1,12 +1,27 @@
 mod app;
+mod characters;
+mod cjk;
+mod clipboard;
+mod dictionary;
+mod documents;
+mod drill;
+mod exercises;
+mod goals;
+mod handwriting;
 mod hanzi_logging;
+mod hsk;
 mod llm;
 mod model;
 mod persistence;
+mod pinyin;
+mod reader;
 mod screensize;
+mod script;
 mod settings;
 mod shortcuts;
+mod statistics;
 mod transform;
+mod tts;
 mod ui;
 
 use diesel_migrations::embed_migrations;
@@ -22,6 +37,20 @@ async fn main() -> eframe::Result {
     let database_path = persistence::database_migration::run(MIGRATIONS)
         .expect("Database migration needs to run before start of the application");
 
+    let import_path = database_path.clone();
+    std::thread::spawn(move || characters::import_if_missing(&import_path));
+
+    let converter = script::Converter::load(&database_path);
+    script::backfill(&database_path, &converter);
+
+    let hsk_imported = hsk::import_if_missing(&database_path);
+    let hsk_index = hsk::Index::load(&database_path);
+    if hsk_imported {
+        hsk::annotate_all(&database_path, &hsk_index, &converter);
+    }
+
+    let dictionary = dictionary::Dictionary::load(&database_path);
+
     let local_llm_models = llm::list_local_model_names()
         .await
         .expect("At least one LLM model should be installed locally with Ollama.");
@@ -31,8 +60,7 @@ async fn main() -> eframe::Result {
         .find(|model| model.starts_with("mistral"))
         .unwrap_or(local_llm_models.first());
 
-    let selected_llm_model =
-        settings::load_setting(&database_path, model::SettingName::LlmModel).unwrap_or(default_llm_model.to_owned());
+    let settings = settings::load(&database_path, default_llm_model);
 
     let screen_size = screensize::get_primary_screen_size();
 
@@ -51,7 +79,10 @@ async fn main() -> eframe::Result {
             Ok(Box::new(app::HanziApp::new(
                 cc,
                 local_llm_models.into(),
-                selected_llm_model,
+                settings,
+                converter,
+                hsk_index,
+                dictionary,
                 database_path,
             )))
         }),
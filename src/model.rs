@@ -1,14 +1,400 @@
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Display;
+use std::ops::Range;
 use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error as ThisError;
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 pub(crate) struct Phrase {
     pub(crate) original: String,
     pub(crate) pinyin: String,
+    #[serde(skip)]
+    pub(crate) language: String,
     pub(crate) translation: String,
+    #[serde(default)]
+    pub(crate) senses: Vec<Sense>,
+    #[serde(default)]
+    pub(crate) examples: Vec<Example>,
+    #[serde(skip)]
+    pub(crate) document_line: Option<i32>,
+}
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize)]
+pub(crate) struct Sense {
+    pub(crate) translation: String,
+    #[serde(default)]
+    pub(crate) note: String,
+}
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize)]
+pub(crate) struct Example {
+    pub(crate) hanzi: String,
+    pub(crate) pinyin: String,
+    pub(crate) translation: String,
+}
+
+pub(crate) const DEFAULT_LANGUAGE: &str = "English";
+
+pub(crate) const LANGUAGES: [&str; 12] = [
+    "English",
+    "German",
+    "French",
+    "Spanish",
+    "Italian",
+    "Portuguese",
+    "Dutch",
+    "Polish",
+    "Russian",
+    "Ukrainian",
+    "Japanese",
+    "Korean",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Script {
+    #[default]
+    Simplified,
+    Traditional,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum HskVersion {
+    #[default]
+    Hsk2,
+    Hsk3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Tone {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Neutral,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Syllable {
+    pub(crate) range: Range<usize>,
+    pub(crate) tone: Tone,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SyllableCheck {
+    pub(crate) expected: String,
+    pub(crate) expected_tone: Tone,
+    pub(crate) answered: Option<(String, Tone)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ToneStatistics {
+    pub(crate) tones: Vec<(Tone, usize, usize)>,
+    pub(crate) confused_pairs: Vec<ConfusedPair>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ConfusedPair {
+    pub(crate) expected: (Tone, Tone),
+    pub(crate) answered: (Option<Tone>, Option<Tone>),
+    pub(crate) count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Segment {
+    pub(crate) text: String,
+    pub(crate) sentence: usize,
+    pub(crate) line: usize,
+    pub(crate) is_word: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Ruby {
+    pub(crate) text: String,
+    pub(crate) reading: Option<(String, Tone)>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum PinyinLayout {
+    #[default]
+    Column,
+    Ruby,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ToneColors {
+    pub(crate) enabled: bool,
+    pub(crate) palette: [[u8; 3]; 5],
+}
+
+pub(crate) const PLECO_PALETTE: [[u8; 3]; 5] = [
+    [0xe3, 0x00, 0x00],
+    [0x02, 0xb3, 0x1c],
+    [0x15, 0x10, 0xf0],
+    [0x89, 0x00, 0xbf],
+    [0x77, 0x77, 0x77],
+];
+
+pub(crate) const MDBG_PALETTE: [[u8; 3]; 5] = [
+    [0xe6, 0x00, 0x00],
+    [0xd8, 0x90, 0x00],
+    [0x00, 0xa0, 0x00],
+    [0x1e, 0x64, 0xff],
+    [0x9a, 0x9a, 0x9a],
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum TtsBackend {
+    #[default]
+    Command,
+    Http,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TtsSettings {
+    pub(crate) backend: TtsBackend,
+    pub(crate) command: String,
+    pub(crate) url: String,
+}
+
+pub(crate) const DEFAULT_TTS_COMMAND: &str = "espeak-ng -v cmn -w {output} {text}";
+pub(crate) const DEFAULT_TTS_URL: &str = "http://localhost:5002/api/tts?text={text}";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Direction {
+    #[default]
+    Detect,
+    FromChinese,
+    IntoChinese,
+}
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub(crate) struct GenerationOptions {
+    pub(crate) temperature: Option<f32>,
+    pub(crate) top_p: Option<f32>,
+    pub(crate) num_ctx: Option<i32>,
+    pub(crate) seed: Option<i32>,
+    pub(crate) system_prompt: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PromptKind {
+    Translate,
+    ReverseTranslate,
+    Examples,
+}
+
+impl PromptKind {
+    pub(crate) const ALL: [PromptKind; 3] = [
+        PromptKind::Translate,
+        PromptKind::ReverseTranslate,
+        PromptKind::Examples,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            PromptKind::Translate => "Translation",
+            PromptKind::ReverseTranslate => "Reverse translation",
+            PromptKind::Examples => "Example sentences",
+        }
+    }
+
+    pub(crate) fn placeholders(&self) -> &'static str {
+        match self {
+            PromptKind::Translate | PromptKind::ReverseTranslate => "{text}, {target_language}, {format}",
+            PromptKind::Examples => "{text}, {target_language}, {format}, {count}",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PromptTemplates {
+    pub(crate) translate: String,
+    pub(crate) reverse_translate: String,
+    pub(crate) examples: String,
+}
+
+impl PromptTemplates {
+    pub(crate) fn get(&self, kind: PromptKind) -> &str {
+        match kind {
+            PromptKind::Translate => &self.translate,
+            PromptKind::ReverseTranslate => &self.reverse_translate,
+            PromptKind::Examples => &self.examples,
+        }
+    }
+
+    pub(crate) fn set(&mut self, kind: PromptKind, template: String) {
+        match kind {
+            PromptKind::Translate => self.translate = template,
+            PromptKind::ReverseTranslate => self.reverse_translate = template,
+            PromptKind::Examples => self.examples = template,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum DocumentFormat {
+    #[default]
+    Text,
+    Epub,
+    Srt,
+    Ass,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Document {
+    pub(crate) id: i32,
+    pub(crate) title: String,
+    pub(crate) path: String,
+    pub(crate) format: DocumentFormat,
+    pub(crate) created_at: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DocumentLine {
+    pub(crate) id: i32,
+    pub(crate) document_id: i32,
+    pub(crate) position: usize,
+    pub(crate) reference: String,
+    pub(crate) text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum QueueStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Saved,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct QueueItem {
+    pub(crate) id: i32,
+    pub(crate) text: String,
+    pub(crate) language: String,
+    pub(crate) status: QueueStatus,
+    pub(crate) result: Option<Phrase>,
+    pub(crate) error: Option<String>,
+    pub(crate) direction: Direction,
+    pub(crate) llm_model: String,
+}
+
+#[derive(Clone, Default, Debug)]
+pub(crate) struct Character {
+    pub(crate) character: String,
+    pub(crate) definition: String,
+    pub(crate) pinyin: String,
+    pub(crate) radical: String,
+    pub(crate) components: Vec<String>,
+    pub(crate) stroke_count: Option<i32>,
+    pub(crate) related: Vec<String>,
+    pub(crate) strokes: Vec<Stroke>,
+}
+
+#[derive(Clone, Default, Debug)]
+pub(crate) struct Stroke {
+    pub(crate) outline: Vec<[f32; 2]>,
+    pub(crate) median: Vec<[f32; 2]>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StrokeFeedback {
+    Correct,
+    WrongDirection,
+    WrongOrder(usize),
+    Mismatch,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ExerciseKind {
+    #[default]
+    Recognition,
+    Recall,
+    Listening,
+    Cloze,
+    ToneDrill,
+    Handwriting,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EventKind {
+    Save,
+    Review,
+    Lookup,
+    Study,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DailyGoals {
+    pub(crate) new_phrases: u32,
+    pub(crate) reviews: u32,
+    pub(crate) minutes: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ClipboardSettings {
+    pub(crate) watch: bool,
+    pub(crate) llm_fallback: bool,
+    pub(crate) interval_secs: u32,
+    pub(crate) ignore: String,
+}
+
+pub(crate) const DEFAULT_CLIPBOARD_INTERVAL_SECS: u32 = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GoalProgress {
+    pub(crate) new_phrases: u32,
+    pub(crate) reviews: u32,
+    pub(crate) minutes: u32,
+    pub(crate) streak: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Statistics {
+    pub(crate) saved_per_day: Vec<usize>,
+    pub(crate) reviews_per_day: Vec<usize>,
+    pub(crate) lookups_per_day: Vec<usize>,
+    pub(crate) retention: Vec<(ExerciseKind, usize, usize)>,
+    pub(crate) due_forecast: Vec<usize>,
+    pub(crate) hardest: Vec<(String, usize, usize)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Schedule {
+    pub(crate) repetitions: i32,
+    pub(crate) interval_days: i32,
+    pub(crate) ease: f32,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Card {
+    pub(crate) phrase: Phrase,
+    pub(crate) choices: Vec<String>,
+    pub(crate) cloze: Option<Example>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Lookup {
+    pub(crate) input: String,
+    pub(crate) llm_model: String,
+    pub(crate) result: Option<Phrase>,
+    pub(crate) error: Option<String>,
+    pub(crate) duration: Duration,
+    pub(crate) created_at: String,
+}
+
+#[derive(ThisError, Debug)]
+pub(crate) enum ModelError {
+    #[error("Unknown queue status: {0}")]
+    UnknownQueueStatus(String),
+    #[error("Unknown translation direction: {0}")]
+    UnknownDirection(String),
+    #[error("Unknown exercise: {0}")]
+    UnknownExercise(String),
+    #[error("Unsupported document format: {0}")]
+    UnsupportedDocumentFormat(String),
 }
 
 #[derive(ThisError, Debug)]
@@ -23,6 +409,24 @@ pub(crate) enum SettingError {
 
 pub(crate) enum SettingName {
     LlmModel,
+    TargetLanguage,
+    ComparisonModels,
+    MaxAttempts,
+    PreferredScript,
+    HskVersion,
+    TtsBackend,
+    TtsCommand,
+    TtsUrl,
+    ToneColoring,
+    ToneColors,
+    PinyinLayout,
+    DailyNewPhrases,
+    DailyReviews,
+    DailyMinutes,
+    ClipboardWatch,
+    ClipboardLlmFallback,
+    ClipboardInterval,
+    ClipboardIgnore,
 }
 
 pub(crate) struct Setting {
@@ -30,10 +434,266 @@ pub(crate) struct Setting {
     pub(crate) value: String,
 }
 
+impl Script {
+    pub(crate) const ALL: [Script; 2] = [Script::Simplified, Script::Traditional];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Script::Simplified => "Simplified",
+            Script::Traditional => "Traditional",
+        }
+    }
+
+    pub(crate) fn other(&self) -> Script {
+        match self {
+            Script::Simplified => Script::Traditional,
+            Script::Traditional => Script::Simplified,
+        }
+    }
+}
+
+impl HskVersion {
+    pub(crate) const ALL: [HskVersion; 2] = [HskVersion::Hsk2, HskVersion::Hsk3];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            HskVersion::Hsk2 => "HSK 2.0",
+            HskVersion::Hsk3 => "HSK 3.0",
+        }
+    }
+
+    pub(crate) fn major(&self) -> u8 {
+        match self {
+            HskVersion::Hsk2 => 2,
+            HskVersion::Hsk3 => 3,
+        }
+    }
+
+    pub(crate) fn levels(&self) -> std::ops::RangeInclusive<i32> {
+        match self {
+            HskVersion::Hsk2 => 1..=6,
+            HskVersion::Hsk3 => 1..=9,
+        }
+    }
+}
+
+impl Tone {
+    pub(crate) const ALL: [Tone; 5] = [Tone::First, Tone::Second, Tone::Third, Tone::Fourth, Tone::Neutral];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Tone::First => "1st",
+            Tone::Second => "2nd",
+            Tone::Third => "3rd",
+            Tone::Fourth => "4th",
+            Tone::Neutral => "Neutral",
+        }
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            Tone::First => 0,
+            Tone::Second => 1,
+            Tone::Third => 2,
+            Tone::Fourth => 3,
+            Tone::Neutral => 4,
+        }
+    }
+
+    pub(crate) fn number(&self) -> i32 {
+        self.index() as i32 + 1
+    }
+
+    pub(crate) fn from_number(number: i32) -> Option<Tone> {
+        usize::try_from(number - 1)
+            .ok()
+            .and_then(|index| Tone::ALL.get(index))
+            .copied()
+    }
+}
+
+impl ExerciseKind {
+    pub(crate) const ALL: [ExerciseKind; 6] = [
+        ExerciseKind::Recognition,
+        ExerciseKind::Recall,
+        ExerciseKind::Listening,
+        ExerciseKind::Cloze,
+        ExerciseKind::ToneDrill,
+        ExerciseKind::Handwriting,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            ExerciseKind::Recognition => "Recognition",
+            ExerciseKind::Recall => "Recall",
+            ExerciseKind::Listening => "Listening",
+            ExerciseKind::Cloze => "Cloze",
+            ExerciseKind::ToneDrill => "Tone drill",
+            ExerciseKind::Handwriting => "Handwriting",
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            ExerciseKind::Recognition => "See the hanzi, remember the meaning",
+            ExerciseKind::Recall => "See the meaning, type the hanzi",
+            ExerciseKind::Listening => "Read or hear the pinyin, pick its hanzi",
+            ExerciseKind::Cloze => "Fill the gap in a saved example sentence",
+            ExerciseKind::ToneDrill => "Type the pinyin with tone numbers",
+            ExerciseKind::Handwriting => "Write the characters stroke by stroke",
+        }
+    }
+}
+
+impl DailyGoals {
+    pub(crate) fn is_set(&self) -> bool {
+        self.new_phrases > 0 || self.reviews > 0 || self.minutes > 0
+    }
+
+    pub(crate) fn is_met(&self, progress: &GoalProgress) -> bool {
+        progress.new_phrases >= self.new_phrases && progress.reviews >= self.reviews && progress.minutes >= self.minutes
+    }
+}
+
+impl Default for DailyGoals {
+    fn default() -> Self {
+        Self {
+            new_phrases: 5,
+            reviews: 20,
+            minutes: 10,
+        }
+    }
+}
+
+impl SyllableCheck {
+    pub(crate) fn is_correct(&self) -> bool {
+        self.answered
+            .as_ref()
+            .is_some_and(|(text, tone)| *text == self.expected && *tone == self.expected_tone)
+    }
+
+    pub(crate) fn answered_tone(&self) -> Option<Tone> {
+        self.answered.as_ref().map(|(_, tone)| *tone)
+    }
+}
+
+impl PinyinLayout {
+    pub(crate) const ALL: [PinyinLayout; 2] = [PinyinLayout::Column, PinyinLayout::Ruby];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            PinyinLayout::Column => "Separate column",
+            PinyinLayout::Ruby => "Above characters",
+        }
+    }
+}
+
+impl ToneColors {
+    pub(crate) fn color(&self, tone: Tone) -> Option<[u8; 3]> {
+        self.enabled.then_some(self.palette[tone.index()])
+    }
+
+    pub(crate) fn palette_to_string(&self) -> String {
+        self.palette
+            .iter()
+            .map(|[r, g, b]| format!("{:02x}{:02x}{:02x}", r, g, b))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub(crate) fn parse_palette(value: &str) -> Option<[[u8; 3]; 5]> {
+        let colors = value
+            .split(',')
+            .map(|hex| {
+                let rgb = u32::from_str_radix(hex.trim(), 16)
+                    .ok()
+                    .filter(|_| hex.trim().len() == 6)?;
+                Some([(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8])
+            })
+            .collect::<Option<Vec<_>>>()?;
+        colors.try_into().ok()
+    }
+}
+
+impl Default for ToneColors {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            palette: PLECO_PALETTE,
+        }
+    }
+}
+
+impl TtsBackend {
+    pub(crate) const ALL: [TtsBackend; 2] = [TtsBackend::Command, TtsBackend::Http];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            TtsBackend::Command => "Local command",
+            TtsBackend::Http => "HTTP server",
+        }
+    }
+}
+
+impl ClipboardSettings {
+    pub(crate) fn is_ignored(&self, text: &str) -> bool {
+        self.ignore.lines().map(str::trim).any(|ignored| ignored == text)
+    }
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            llm_fallback: false,
+            interval_secs: DEFAULT_CLIPBOARD_INTERVAL_SECS,
+            ignore: String::new(),
+        }
+    }
+}
+
+impl Default for TtsSettings {
+    fn default() -> Self {
+        Self {
+            backend: TtsBackend::default(),
+            command: DEFAULT_TTS_COMMAND.to_owned(),
+            url: DEFAULT_TTS_URL.to_owned(),
+        }
+    }
+}
+
+impl Direction {
+    pub(crate) fn label(&self, language: &str) -> String {
+        match self {
+            Direction::Detect => "Detect".to_string(),
+            Direction::FromChinese => format!("Chinese → {}", language),
+            Direction::IntoChinese => format!("{} → Chinese", language),
+        }
+    }
+}
+
 impl Display for SettingName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             SettingName::LlmModel => write!(f, "llm_model"),
+            SettingName::TargetLanguage => write!(f, "target_language"),
+            SettingName::ComparisonModels => write!(f, "comparison_models"),
+            SettingName::MaxAttempts => write!(f, "max_attempts"),
+            SettingName::PreferredScript => write!(f, "preferred_script"),
+            SettingName::HskVersion => write!(f, "hsk_version"),
+            SettingName::TtsBackend => write!(f, "tts_backend"),
+            SettingName::TtsCommand => write!(f, "tts_command"),
+            SettingName::TtsUrl => write!(f, "tts_url"),
+            SettingName::ToneColoring => write!(f, "tone_coloring"),
+            SettingName::ToneColors => write!(f, "tone_colors"),
+            SettingName::PinyinLayout => write!(f, "pinyin_layout"),
+            SettingName::DailyNewPhrases => write!(f, "daily_new_phrases"),
+            SettingName::DailyReviews => write!(f, "daily_reviews"),
+            SettingName::DailyMinutes => write!(f, "daily_minutes"),
+            SettingName::ClipboardWatch => write!(f, "clipboard_watch"),
+            SettingName::ClipboardLlmFallback => write!(f, "clipboard_llm_fallback"),
+            SettingName::ClipboardInterval => write!(f, "clipboard_interval"),
+            SettingName::ClipboardIgnore => write!(f, "clipboard_ignore"),
         }
     }
 }
@@ -44,7 +704,279 @@ impl FromStr for SettingName {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "llm_model" => Ok(SettingName::LlmModel),
+            "target_language" => Ok(SettingName::TargetLanguage),
+            "comparison_models" => Ok(SettingName::ComparisonModels),
+            "max_attempts" => Ok(SettingName::MaxAttempts),
+            "preferred_script" => Ok(SettingName::PreferredScript),
+            "hsk_version" => Ok(SettingName::HskVersion),
+            "tts_backend" => Ok(SettingName::TtsBackend),
+            "tts_command" => Ok(SettingName::TtsCommand),
+            "tts_url" => Ok(SettingName::TtsUrl),
+            "tone_coloring" => Ok(SettingName::ToneColoring),
+            "tone_colors" => Ok(SettingName::ToneColors),
+            "pinyin_layout" => Ok(SettingName::PinyinLayout),
+            "daily_new_phrases" => Ok(SettingName::DailyNewPhrases),
+            "daily_reviews" => Ok(SettingName::DailyReviews),
+            "daily_minutes" => Ok(SettingName::DailyMinutes),
+            "clipboard_watch" => Ok(SettingName::ClipboardWatch),
+            "clipboard_llm_fallback" => Ok(SettingName::ClipboardLlmFallback),
+            "clipboard_interval" => Ok(SettingName::ClipboardInterval),
+            "clipboard_ignore" => Ok(SettingName::ClipboardIgnore),
+            other => Err(SettingError::Unknown(other.to_string())),
+        }
+    }
+}
+
+impl Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Script::Simplified => write!(f, "simplified"),
+            Script::Traditional => write!(f, "traditional"),
+        }
+    }
+}
+
+impl FromStr for Script {
+    type Err = SettingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "simplified" => Ok(Script::Simplified),
+            "traditional" => Ok(Script::Traditional),
             other => Err(SettingError::Unknown(other.to_string())),
         }
     }
 }
+
+impl Display for HskVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HskVersion::Hsk2 => write!(f, "2.0"),
+            HskVersion::Hsk3 => write!(f, "3.0"),
+        }
+    }
+}
+
+impl FromStr for HskVersion {
+    type Err = SettingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2.0" => Ok(HskVersion::Hsk2),
+            "3.0" => Ok(HskVersion::Hsk3),
+            other => Err(SettingError::Unknown(other.to_string())),
+        }
+    }
+}
+
+impl Display for PinyinLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinyinLayout::Column => write!(f, "column"),
+            PinyinLayout::Ruby => write!(f, "ruby"),
+        }
+    }
+}
+
+impl FromStr for PinyinLayout {
+    type Err = SettingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "column" => Ok(PinyinLayout::Column),
+            "ruby" => Ok(PinyinLayout::Ruby),
+            other => Err(SettingError::Unknown(other.to_string())),
+        }
+    }
+}
+
+impl Display for TtsBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TtsBackend::Command => write!(f, "command"),
+            TtsBackend::Http => write!(f, "http"),
+        }
+    }
+}
+
+impl FromStr for TtsBackend {
+    type Err = SettingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "command" => Ok(TtsBackend::Command),
+            "http" => Ok(TtsBackend::Http),
+            other => Err(SettingError::Unknown(other.to_string())),
+        }
+    }
+}
+
+impl Display for PromptKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptKind::Translate => write!(f, "translate"),
+            PromptKind::ReverseTranslate => write!(f, "reverse_translate"),
+            PromptKind::Examples => write!(f, "examples"),
+        }
+    }
+}
+
+impl FromStr for PromptKind {
+    type Err = SettingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "translate" => Ok(PromptKind::Translate),
+            "reverse_translate" => Ok(PromptKind::ReverseTranslate),
+            "examples" => Ok(PromptKind::Examples),
+            other => Err(SettingError::Unknown(other.to_string())),
+        }
+    }
+}
+
+impl Display for QueueStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueueStatus::Pending => write!(f, "pending"),
+            QueueStatus::Running => write!(f, "running"),
+            QueueStatus::Done => write!(f, "done"),
+            QueueStatus::Failed => write!(f, "failed"),
+            QueueStatus::Saved => write!(f, "saved"),
+        }
+    }
+}
+
+impl DocumentFormat {
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "txt" | "text" => Some(DocumentFormat::Text),
+            "epub" => Some(DocumentFormat::Epub),
+            "srt" => Some(DocumentFormat::Srt),
+            "ass" | "ssa" => Some(DocumentFormat::Ass),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            DocumentFormat::Text => "Text",
+            DocumentFormat::Epub => "EPUB",
+            DocumentFormat::Srt => "SRT subtitles",
+            DocumentFormat::Ass => "ASS subtitles",
+        }
+    }
+}
+
+impl Display for DocumentFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentFormat::Text => write!(f, "text"),
+            DocumentFormat::Epub => write!(f, "epub"),
+            DocumentFormat::Srt => write!(f, "srt"),
+            DocumentFormat::Ass => write!(f, "ass"),
+        }
+    }
+}
+
+impl FromStr for DocumentFormat {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(DocumentFormat::Text),
+            "epub" => Ok(DocumentFormat::Epub),
+            "srt" => Ok(DocumentFormat::Srt),
+            "ass" => Ok(DocumentFormat::Ass),
+            other => Err(ModelError::UnsupportedDocumentFormat(other.to_string())),
+        }
+    }
+}
+
+impl FromStr for ExerciseKind {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recognition" => Ok(ExerciseKind::Recognition),
+            "recall" => Ok(ExerciseKind::Recall),
+            "listening" => Ok(ExerciseKind::Listening),
+            "cloze" => Ok(ExerciseKind::Cloze),
+            "tone_drill" => Ok(ExerciseKind::ToneDrill),
+            "handwriting" => Ok(ExerciseKind::Handwriting),
+            other => Err(ModelError::UnknownExercise(other.to_string())),
+        }
+    }
+}
+
+impl Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventKind::Save => write!(f, "save"),
+            EventKind::Review => write!(f, "review"),
+            EventKind::Lookup => write!(f, "lookup"),
+            EventKind::Study => write!(f, "study"),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Detect => write!(f, "detect"),
+            Direction::FromChinese => write!(f, "from_chinese"),
+            Direction::IntoChinese => write!(f, "into_chinese"),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "detect" => Ok(Direction::Detect),
+            "from_chinese" => Ok(Direction::FromChinese),
+            "into_chinese" => Ok(Direction::IntoChinese),
+            other => Err(ModelError::UnknownDirection(other.to_string())),
+        }
+    }
+}
+
+impl FromStr for QueueStatus {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(QueueStatus::Pending),
+            "running" => Ok(QueueStatus::Running),
+            "done" => Ok(QueueStatus::Done),
+            "failed" => Ok(QueueStatus::Failed),
+            "saved" => Ok(QueueStatus::Saved),
+            other => Err(ModelError::UnknownQueueStatus(other.to_string())),
+        }
+    }
+}
+
+impl StrokeFeedback {
+    pub(crate) fn message(&self) -> String {
+        match self {
+            StrokeFeedback::Correct => "Correct".to_string(),
+            StrokeFeedback::WrongDirection => "Right stroke, wrong direction".to_string(),
+            StrokeFeedback::WrongOrder(index) => format!("Wrong order, this is stroke {}", index + 1),
+            StrokeFeedback::Mismatch => "This stroke does not match".to_string(),
+        }
+    }
+}
+
+impl Display for ExerciseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExerciseKind::Recognition => write!(f, "recognition"),
+            ExerciseKind::Recall => write!(f, "recall"),
+            ExerciseKind::Listening => write!(f, "listening"),
+            ExerciseKind::Cloze => write!(f, "cloze"),
+            ExerciseKind::ToneDrill => write!(f, "tone_drill"),
+            ExerciseKind::Handwriting => write!(f, "handwriting"),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use diesel::prelude::*;
 
 pub(crate) fn create(database_url: &str) -> Result<SqliteConnection, super::PersistenceError> {
-    let connection = SqliteConnection::establish(database_url)?;
+    let mut connection = SqliteConnection::establish(database_url)?;
+    diesel::sql_query("PRAGMA foreign_keys = ON").execute(&mut connection)?;
+    diesel::sql_query("PRAGMA busy_timeout = 5000").execute(&mut connection)?;
     Ok(connection)
 }
@@ -1,3 +1,5 @@
+use diesel::Connection;
+use diesel::SqliteConnection;
 use diesel_migrations::EmbeddedMigrations;
 use diesel_migrations::MigrationHarness;
 
@@ -11,7 +13,8 @@ pub(crate) fn run(migrations: EmbeddedMigrations) -> Result<String, super::Persi
 
     let database_path = format!("{}/data.db", hanzi_dir);
 
-    let mut connection = super::database_connection::create(&database_path)?;
+    // Table rebuilds in migrations drop referenced tables, which must not cascade
+    let mut connection = SqliteConnection::establish(&database_path)?;
 
     connection
         .run_pending_migrations(migrations)
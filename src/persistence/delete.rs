@@ -0,0 +1,64 @@
+use diesel::associations::HasTable;
+use diesel::prelude::*;
+
+use crate::persistence::database_connection;
+
+pub(crate) fn prompt_template(database_url: &str, template_name: &str) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::prompt_templates::dsl::*;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::delete(prompt_templates::table().filter(name.eq(template_name)))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to delete prompt template {}: {:?}", template_name, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn queue_item(database_url: &str, item_id: i32) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::queue_items;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::delete(queue_items::table.filter(queue_items::id.eq(item_id)))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to delete queue item {}: {:?}", item_id, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn queue_items(database_url: &str, item_status: &str) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::queue_items;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::delete(queue_items::table.filter(queue_items::status.eq(item_status)))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to delete queue items with status {}: {:?}", item_status, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn lookups(database_url: &str) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::lookups;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::delete(lookups::table)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to delete lookups: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn document(database_url: &str, document_id: i32) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::documents;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::delete(documents::table.filter(documents::id.eq(document_id)))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to delete document {}: {:?}", document_id, error))?;
+
+    Ok(result)
+}
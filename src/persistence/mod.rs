@@ -1,5 +1,6 @@
 pub mod database_connection;
 pub mod database_migration;
+pub mod delete;
 pub mod model;
 pub mod read;
 pub mod schema;
@@ -2,25 +2,301 @@ use diesel::prelude::*;
 
 use crate::persistence::schema;
 
-#[derive(Selectable, Queryable)]
+#[derive(Selectable, Queryable, Identifiable)]
 #[diesel(table_name = schema::phrases)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub(crate) struct Phrase {
     pub(crate) id: i32,
     pub(crate) original: String,
     pub(crate) pinyin: String,
-    pub(crate) translation: String,
+    pub(crate) document_line_id: Option<i32>,
 }
 
+pub(crate) type StoredPhrase = (Phrase, Option<Translation>, Vec<Sense>, Vec<Example>);
+
 #[derive(AsChangeset, Insertable)]
 #[diesel(table_name = schema::phrases)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
-pub(super) struct NewPhrase {
-    pub(super) original: String,
+pub(crate) struct NewPhrase {
+    pub(crate) original: String,
     pub(crate) pinyin: String,
+    pub(crate) simplified: String,
+    pub(crate) traditional: String,
+    pub(crate) hsk2_level: Option<i32>,
+    pub(crate) hsk3_level: Option<i32>,
+    pub(crate) document_line_id: Option<i32>,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Associations)]
+#[diesel(belongs_to(Phrase))]
+#[diesel(table_name = schema::translations)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Translation {
+    pub(crate) id: i32,
+    pub(crate) phrase_id: i32,
+    pub(crate) language: String,
+    pub(crate) translation: String,
+}
+
+#[derive(AsChangeset, Insertable)]
+#[diesel(table_name = schema::translations)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewTranslation {
+    pub(super) phrase_id: i32,
+    pub(super) language: String,
     pub(super) translation: String,
 }
 
+#[derive(Selectable, Queryable, Identifiable, Associations)]
+#[diesel(belongs_to(Phrase))]
+#[diesel(table_name = schema::senses)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Sense {
+    pub(crate) id: i32,
+    pub(crate) phrase_id: i32,
+    pub(crate) position: i32,
+    pub(crate) translation: String,
+    pub(crate) note: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::senses)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewSense {
+    pub(super) phrase_id: i32,
+    pub(super) position: i32,
+    pub(super) translation: String,
+    pub(super) note: String,
+    pub(super) language: String,
+}
+
+#[derive(Selectable, Queryable, Identifiable, Associations)]
+#[diesel(belongs_to(Phrase))]
+#[diesel(table_name = schema::examples)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Example {
+    pub(crate) id: i32,
+    pub(crate) phrase_id: i32,
+    pub(crate) hanzi: String,
+    pub(crate) pinyin: String,
+    pub(crate) translation: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::examples)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewExample {
+    pub(super) phrase_id: i32,
+    pub(super) hanzi: String,
+    pub(super) pinyin: String,
+    pub(super) translation: String,
+    pub(super) language: String,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::model_options)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct ModelOptions {
+    pub(crate) temperature: Option<f32>,
+    pub(crate) top_p: Option<f32>,
+    pub(crate) num_ctx: Option<i32>,
+    pub(crate) seed: Option<i32>,
+    pub(crate) system_prompt: String,
+}
+
+#[derive(AsChangeset, Insertable)]
+#[diesel(table_name = schema::model_options)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewModelOptions {
+    pub(super) model: String,
+    pub(super) temperature: Option<f32>,
+    pub(super) top_p: Option<f32>,
+    pub(super) num_ctx: Option<i32>,
+    pub(super) seed: Option<i32>,
+    pub(super) system_prompt: String,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::prompt_templates)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct PromptTemplate {
+    pub(crate) name: String,
+    pub(crate) template: String,
+}
+
+#[derive(AsChangeset, Insertable)]
+#[diesel(table_name = schema::prompt_templates)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewPromptTemplate {
+    pub(super) name: String,
+    pub(super) template: String,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::queue_items)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct QueueItem {
+    pub(crate) id: i32,
+    pub(crate) text: String,
+    pub(crate) language: String,
+    pub(crate) status: String,
+    pub(crate) result: Option<String>,
+    pub(crate) error: Option<String>,
+    pub(crate) document_line_id: Option<i32>,
+    pub(crate) direction: String,
+    pub(crate) llm_model: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::queue_items)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewQueueItem {
+    pub(super) text: String,
+    pub(super) language: String,
+    pub(super) status: String,
+    pub(super) document_line_id: Option<i32>,
+    pub(super) direction: String,
+    pub(super) llm_model: String,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::characters)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Character {
+    pub(crate) character: String,
+    pub(crate) definition: String,
+    pub(crate) pinyin: String,
+    pub(crate) decomposition: String,
+    pub(crate) radical: String,
+    pub(crate) stroke_count: Option<i32>,
+}
+
+#[derive(AsChangeset, Insertable)]
+#[diesel(table_name = schema::characters)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewCharacter {
+    pub(crate) character: String,
+    pub(crate) definition: String,
+    pub(crate) pinyin: String,
+    pub(crate) decomposition: String,
+    pub(crate) radical: String,
+    pub(crate) stroke_count: Option<i32>,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::strokes)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Stroke {
+    pub(crate) path: String,
+    pub(crate) median: String,
+}
+
+#[derive(AsChangeset, Insertable)]
+#[diesel(table_name = schema::strokes)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewStroke {
+    pub(crate) character: String,
+    pub(crate) position: i32,
+    pub(crate) path: String,
+    pub(crate) median: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::events)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewEvent {
+    pub(super) kind: String,
+    pub(super) item: String,
+    pub(super) duration_ms: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::exercise_results)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewExerciseResult {
+    pub(super) exercise: String,
+    pub(super) item: String,
+    pub(super) correct: bool,
+    pub(super) mistakes: i32,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::schedules)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Schedule {
+    pub(crate) item: String,
+    pub(crate) repetitions: i32,
+    pub(crate) interval_days: i32,
+    pub(crate) ease: f32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::schedules)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewSchedule {
+    pub(super) item: String,
+    pub(super) exercise: String,
+    pub(super) repetitions: i32,
+    pub(super) interval_days: i32,
+    pub(super) ease: f32,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::tone_answers)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct ToneAnswer {
+    pub(crate) expected: i32,
+    pub(crate) answered: Option<i32>,
+    pub(crate) previous_expected: Option<i32>,
+    pub(crate) previous_answered: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::tone_answers)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewToneAnswer {
+    pub(crate) item: String,
+    pub(crate) expected: i32,
+    pub(crate) answered: Option<i32>,
+    pub(crate) previous_expected: Option<i32>,
+    pub(crate) previous_answered: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::hsk_words)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewHskWord {
+    pub(crate) word: String,
+    pub(crate) version: String,
+    pub(crate) level: i32,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::lookups)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Lookup {
+    pub(crate) input: String,
+    pub(crate) model: String,
+    pub(crate) language: String,
+    pub(crate) result: Option<String>,
+    pub(crate) error: Option<String>,
+    pub(crate) duration_ms: i32,
+    pub(crate) created_at: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::lookups)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewLookup {
+    pub(super) input: String,
+    pub(super) model: String,
+    pub(super) language: String,
+    pub(super) result: Option<String>,
+    pub(super) error: Option<String>,
+    pub(super) duration_ms: i32,
+}
+
 #[derive(Selectable, Queryable)]
 #[diesel(table_name = schema::settings)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -37,3 +313,46 @@ pub(super) struct NewSetting {
     pub(crate) name: String,
     pub(crate) value: String,
 }
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::documents)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewDocument {
+    pub(super) title: String,
+    pub(super) path: String,
+    pub(super) format: String,
+    pub(super) fingerprint: String,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::documents)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct Document {
+    pub(crate) id: i32,
+    pub(crate) title: String,
+    pub(crate) path: String,
+    pub(crate) format: String,
+    pub(crate) created_at: String,
+    pub(crate) fingerprint: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = schema::document_lines)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct NewDocumentLine {
+    pub(super) document_id: i32,
+    pub(super) position: i32,
+    pub(super) reference: String,
+    pub(super) text: String,
+}
+
+#[derive(Selectable, Queryable)]
+#[diesel(table_name = schema::document_lines)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct DocumentLine {
+    pub(crate) id: i32,
+    pub(crate) document_id: i32,
+    pub(crate) position: i32,
+    pub(crate) reference: String,
+    pub(crate) text: String,
+}
@@ -1,19 +1,89 @@
 use diesel::associations::HasTable;
 use diesel::prelude::*;
 
+use crate::model::HskVersion;
 use crate::persistence::database_connection;
 use crate::persistence::model;
 
-pub(crate) fn phrases(database_url: &str, term: &str) -> Result<Vec<model::Phrase>, super::PersistenceError> {
-    use crate::persistence::schema::phrases::dsl::*;
+pub(crate) fn phrases(
+    database_url: &str,
+    term: &str,
+    simplified_term: &str,
+    language: &str,
+    hsk_filter: Option<(HskVersion, i32)>,
+) -> Result<Vec<model::StoredPhrase>, super::PersistenceError> {
+    use crate::persistence::schema::examples;
+    use crate::persistence::schema::phrases;
+    use crate::persistence::schema::senses;
+    use crate::persistence::schema::translations;
 
     let mut conn = database_connection::create(database_url)?;
 
-    let result = phrases::table()
-        .filter(original.like(format!("%{}%", term)))
+    let pattern = format!("%{}%", term);
+
+    let matching_translations = translations::table
+        .filter(translations::translation.like(pattern.clone()))
+        .select(translations::phrase_id);
+
+    let matching_senses = senses::table
+        .filter(senses::translation.like(pattern.clone()))
+        .select(senses::phrase_id);
+
+    let mut query = phrases::table
+        .filter(
+            phrases::original
+                .like(pattern.clone())
+                .or(phrases::simplified.like(format!("%{}%", simplified_term)))
+                .or(phrases::id.eq_any(matching_translations))
+                .or(phrases::id.eq_any(matching_senses)),
+        )
+        .into_boxed();
+
+    query = match hsk_filter {
+        Some((HskVersion::Hsk2, level)) => query.filter(phrases::hsk2_level.eq(level)),
+        Some((HskVersion::Hsk3, level)) => query.filter(phrases::hsk3_level.eq(level)),
+        None => query,
+    };
+
+    let found_phrases = query
+        .select(model::Phrase::as_select())
         .load(&mut conn)
         .inspect_err(|error| log::error!("Failed to load phrases by term {}: {:?}", term, error))?;
 
+    let found_translations = model::Translation::belonging_to(&found_phrases)
+        .filter(translations::language.eq(language))
+        .select(model::Translation::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load translations for term {}: {:?}", term, error))?;
+
+    let found_senses = model::Sense::belonging_to(&found_phrases)
+        .filter(senses::language.eq(language))
+        .select(model::Sense::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load senses for term {}: {:?}", term, error))?;
+
+    let found_examples = model::Example::belonging_to(&found_phrases)
+        .filter(examples::language.eq(language))
+        .select(model::Example::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load examples for term {}: {:?}", term, error))?;
+
+    let result = found_translations
+        .grouped_by(&found_phrases)
+        .into_iter()
+        .zip(found_senses.grouped_by(&found_phrases))
+        .zip(found_examples.grouped_by(&found_phrases))
+        .zip(found_phrases)
+        .map(|(((phrase_translations, phrase_senses), phrase_examples), phrase)| {
+            (
+                phrase,
+                phrase_translations.into_iter().next(),
+                phrase_senses,
+                phrase_examples,
+            )
+        })
+        .collect();
+
     Ok(result)
 }
 
@@ -29,3 +99,494 @@ pub(crate) fn setting(database_url: &str, setting_name: &str) -> Result<model::S
 
     Ok(result)
 }
+
+pub(crate) fn prompt_templates(database_url: &str) -> Result<Vec<model::PromptTemplate>, super::PersistenceError> {
+    use crate::persistence::schema::prompt_templates::dsl::*;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = prompt_templates::table()
+        .select(model::PromptTemplate::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load prompt templates: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn model_options(
+    database_url: &str,
+    model_name: &str,
+) -> Result<Option<model::ModelOptions>, super::PersistenceError> {
+    use crate::persistence::schema::model_options;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = model_options::table
+        .filter(model_options::model.eq(model_name))
+        .select(model::ModelOptions::as_select())
+        .first(&mut conn)
+        .optional()
+        .inspect_err(|error| log::error!("Failed to load model options for {}: {:?}", model_name, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn queue_items(database_url: &str) -> Result<Vec<model::QueueItem>, super::PersistenceError> {
+    use crate::persistence::schema::queue_items;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = queue_items::table
+        .order(queue_items::id.asc())
+        .select(model::QueueItem::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load queue items: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn lookups(database_url: &str, limit: i64) -> Result<Vec<model::Lookup>, super::PersistenceError> {
+    use crate::persistence::schema::lookups;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = lookups::table
+        .order(lookups::id.desc())
+        .limit(limit)
+        .select(model::Lookup::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load lookups: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn character(
+    database_url: &str,
+    character_text: &str,
+) -> Result<Option<model::Character>, super::PersistenceError> {
+    use crate::persistence::schema::characters;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = characters::table
+        .filter(characters::character.eq(character_text))
+        .select(model::Character::as_select())
+        .first(&mut conn)
+        .optional()
+        .inspect_err(|error| log::error!("Failed to load character {}: {:?}", character_text, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn related_characters(
+    database_url: &str,
+    character_text: &str,
+    character_radical: &str,
+    limit: i64,
+) -> Result<Vec<String>, super::PersistenceError> {
+    use crate::persistence::schema::characters;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = characters::table
+        .filter(characters::character.ne(character_text))
+        .filter(
+            characters::decomposition
+                .like(format!("%{}%", character_text))
+                .or(characters::radical.eq(character_radical)),
+        )
+        .order(characters::stroke_count.asc())
+        .limit(limit)
+        .select(characters::character)
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load characters related to {}: {:?}", character_text, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn characters_count(database_url: &str) -> Result<i64, super::PersistenceError> {
+    use crate::persistence::schema::characters;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = characters::table
+        .count()
+        .get_result(&mut conn)
+        .inspect_err(|error| log::error!("Failed to count characters: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn strokes(database_url: &str, character_text: &str) -> Result<Vec<model::Stroke>, super::PersistenceError> {
+    use crate::persistence::schema::strokes;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = strokes::table
+        .filter(strokes::character.eq(character_text))
+        .order(strokes::position.asc())
+        .select(model::Stroke::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load strokes of {}: {:?}", character_text, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn strokes_count(database_url: &str) -> Result<i64, super::PersistenceError> {
+    use crate::persistence::schema::strokes;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = strokes::table
+        .count()
+        .get_result(&mut conn)
+        .inspect_err(|error| log::error!("Failed to count strokes: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn phrases_without_scripts(
+    database_url: &str,
+) -> Result<Vec<(i32, String, String, String)>, super::PersistenceError> {
+    use crate::persistence::schema::phrases;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = phrases::table
+        .filter(
+            phrases::simplified
+                .eq("")
+                .or(phrases::traditional.eq(""))
+                .or(phrases::simplified
+                    .eq(phrases::original)
+                    .and(phrases::traditional.eq(phrases::original))),
+        )
+        .select((
+            phrases::id,
+            phrases::original,
+            phrases::simplified,
+            phrases::traditional,
+        ))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load phrases without scripts: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn hsk_words(database_url: &str) -> Result<Vec<(String, String, i32)>, super::PersistenceError> {
+    use crate::persistence::schema::hsk_words;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = hsk_words::table
+        .select((hsk_words::word, hsk_words::version, hsk_words::level))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load HSK words: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn hsk_levels(database_url: &str) -> Result<Vec<(String, i32)>, super::PersistenceError> {
+    use crate::persistence::schema::hsk_words;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = hsk_words::table
+        .select((hsk_words::version, hsk_words::level))
+        .distinct()
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load HSK levels: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn exercise_attempts(
+    database_url: &str,
+    exercise: &str,
+) -> Result<Vec<(String, i64)>, super::PersistenceError> {
+    use crate::persistence::schema::exercise_results;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = exercise_results::table
+        .filter(exercise_results::exercise.eq(exercise))
+        .group_by(exercise_results::item)
+        .select((exercise_results::item, diesel::dsl::count_star()))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to count attempts of exercise {}: {:?}", exercise, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn schedules(
+    database_url: &str,
+    exercise: &str,
+) -> Result<Vec<(model::Schedule, bool)>, super::PersistenceError> {
+    use crate::persistence::schema::schedules;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = schedules::table
+        .filter(schedules::exercise.eq(exercise))
+        .select((model::Schedule::as_select(), due()))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load schedules of exercise {}: {:?}", exercise, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn schedule(
+    database_url: &str,
+    item: &str,
+    exercise: &str,
+) -> Result<Option<model::Schedule>, super::PersistenceError> {
+    use crate::persistence::schema::schedules;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = schedules::table
+        .filter(schedules::item.eq(item))
+        .filter(schedules::exercise.eq(exercise))
+        .select(model::Schedule::as_select())
+        .first(&mut conn)
+        .optional()
+        .inspect_err(|error| log::error!("Failed to load schedule of {} for {}: {:?}", item, exercise, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn tone_answers(database_url: &str) -> Result<Vec<model::ToneAnswer>, super::PersistenceError> {
+    use crate::persistence::schema::tone_answers;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = tone_answers::table
+        .select(model::ToneAnswer::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load tone answers: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn hsk_coverage(
+    database_url: &str,
+    hsk_version: &str,
+) -> Result<Vec<(i32, i64, i64)>, super::PersistenceError> {
+    use crate::persistence::schema::hsk_words;
+    use crate::persistence::schema::phrases;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let totals = hsk_words::table
+        .filter(hsk_words::version.eq(hsk_version))
+        .group_by(hsk_words::level)
+        .select((hsk_words::level, diesel::dsl::count_star()))
+        .order(hsk_words::level.asc())
+        .load::<(i32, i64)>(&mut conn)
+        .inspect_err(|error| log::error!("Failed to count HSK {} words: {:?}", hsk_version, error))?;
+
+    let saved = hsk_words::table
+        .filter(hsk_words::version.eq(hsk_version))
+        .filter(
+            hsk_words::word
+                .eq_any(phrases::table.select(phrases::original))
+                .or(hsk_words::word.eq_any(phrases::table.select(phrases::simplified))),
+        )
+        .group_by(hsk_words::level)
+        .select((hsk_words::level, diesel::dsl::count_star()))
+        .load::<(i32, i64)>(&mut conn)
+        .inspect_err(|error| log::error!("Failed to count saved HSK {} words: {:?}", hsk_version, error))?;
+
+    let result = totals
+        .into_iter()
+        .map(|(level, total)| {
+            let saved = saved
+                .iter()
+                .find(|(saved_level, _)| *saved_level == level)
+                .map_or(0, |(_, count)| *count);
+            (level, total, saved)
+        })
+        .collect();
+
+    Ok(result)
+}
+
+pub(crate) fn phrase_originals(database_url: &str) -> Result<Vec<String>, super::PersistenceError> {
+    use crate::persistence::schema::phrases;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = phrases::table
+        .select(phrases::original)
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load phrases: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn event_days(database_url: &str, event_kind: &str, days: i32) -> Result<Vec<i32>, super::PersistenceError> {
+    use crate::persistence::schema::events;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = events::table
+        .filter(events::kind.eq(event_kind))
+        .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(&format!(
+            "created_at >= date('now', 'localtime', '-{} days')",
+            days - 1
+        )))
+        .select(days_ago("created_at"))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load {} events: {:?}", event_kind, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn first_event_days(database_url: &str, event_kind: &str) -> Result<Vec<i32>, super::PersistenceError> {
+    use crate::persistence::schema::events;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = events::table
+        .filter(events::kind.eq(event_kind))
+        .group_by(events::item)
+        .select(days_ago("MIN(created_at)"))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load first {} events: {:?}", event_kind, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn activity(database_url: &str, days: i32) -> Result<Vec<(String, i32, i32)>, super::PersistenceError> {
+    use crate::persistence::schema::events;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = events::table
+        .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(&format!(
+            "created_at >= date('now', 'localtime', '-{} days')",
+            days - 1
+        )))
+        .select((events::kind, days_ago("created_at"), events::duration_ms))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load the activity log: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn due_days(database_url: &str, days: i32) -> Result<Vec<i32>, super::PersistenceError> {
+    use crate::persistence::schema::schedules;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let due_date = "date(reviewed_at, '+' || interval_days || ' days')";
+    let result = schedules::table
+        .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(&format!(
+            "{} < date('now', 'localtime', '+{} days')",
+            due_date, days
+        )))
+        .select(days_ago(due_date))
+        .load::<i32>(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load due schedules: {:?}", error))?;
+
+    Ok(result.into_iter().map(|days_ago| -days_ago).collect())
+}
+
+pub(crate) fn exercise_outcomes(
+    database_url: &str,
+) -> Result<Vec<(String, String, bool, i32)>, super::PersistenceError> {
+    use crate::persistence::schema::exercise_results;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = exercise_results::table
+        .select((
+            exercise_results::exercise,
+            exercise_results::item,
+            exercise_results::correct,
+            days_ago("created_at"),
+        ))
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load exercise results: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn documents(database_url: &str) -> Result<Vec<model::Document>, super::PersistenceError> {
+    use crate::persistence::schema::documents;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = documents::table
+        .order(documents::id.desc())
+        .select(model::Document::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load documents: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn document(
+    database_url: &str,
+    document_path: &str,
+) -> Result<Option<model::Document>, super::PersistenceError> {
+    use crate::persistence::schema::documents;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = documents::table
+        .filter(documents::path.eq(document_path))
+        .select(model::Document::as_select())
+        .first(&mut conn)
+        .optional()
+        .inspect_err(|error| log::error!("Failed to load document {}: {:?}", document_path, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn document_lines(
+    database_url: &str,
+    document_id: i32,
+) -> Result<Vec<model::DocumentLine>, super::PersistenceError> {
+    use crate::persistence::schema::document_lines;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = document_lines::table
+        .filter(document_lines::document_id.eq(document_id))
+        .order(document_lines::position)
+        .select(model::DocumentLine::as_select())
+        .load(&mut conn)
+        .inspect_err(|error| log::error!("Failed to load lines of document {}: {:?}", document_id, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn document_line(
+    database_url: &str,
+    line_id: i32,
+) -> Result<Option<model::DocumentLine>, super::PersistenceError> {
+    use crate::persistence::schema::document_lines;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = document_lines::table
+        .filter(document_lines::id.eq(line_id))
+        .select(model::DocumentLine::as_select())
+        .first(&mut conn)
+        .optional()
+        .inspect_err(|error| log::error!("Failed to load document line {}: {:?}", line_id, error))?;
+
+    Ok(result)
+}
+
+fn days_ago(column: &str) -> diesel::expression::SqlLiteral<diesel::sql_types::Integer> {
+    diesel::dsl::sql(&format!(
+        "CAST(julianday(date('now', 'localtime')) - julianday(date({})) AS INTEGER)",
+        column
+    ))
+}
+
+fn due() -> diesel::expression::SqlLiteral<diesel::sql_types::Bool> {
+    diesel::dsl::sql("date(reviewed_at, '+' || interval_days || ' days') <= date('now', 'localtime')")
+}
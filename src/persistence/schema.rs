@@ -1,11 +1,159 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    characters (id) {
+        id -> Integer,
+        character -> Text,
+        definition -> Text,
+        pinyin -> Text,
+        decomposition -> Text,
+        radical -> Text,
+        stroke_count -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    document_lines (id) {
+        id -> Integer,
+        document_id -> Integer,
+        position -> Integer,
+        reference -> Text,
+        text -> Text,
+    }
+}
+
+diesel::table! {
+    documents (id) {
+        id -> Integer,
+        title -> Text,
+        path -> Text,
+        format -> Text,
+        created_at -> Text,
+        fingerprint -> Text,
+    }
+}
+
+diesel::table! {
+    events (id) {
+        id -> Integer,
+        kind -> Text,
+        item -> Text,
+        created_at -> Text,
+        duration_ms -> Integer,
+    }
+}
+
+diesel::table! {
+    examples (id) {
+        id -> Integer,
+        phrase_id -> Integer,
+        hanzi -> Text,
+        pinyin -> Text,
+        translation -> Text,
+        language -> Text,
+    }
+}
+
+diesel::table! {
+    exercise_results (id) {
+        id -> Integer,
+        exercise -> Text,
+        item -> Text,
+        correct -> Bool,
+        mistakes -> Integer,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    hsk_words (id) {
+        id -> Integer,
+        word -> Text,
+        version -> Text,
+        level -> Integer,
+    }
+}
+
+diesel::table! {
+    lookups (id) {
+        id -> Integer,
+        input -> Text,
+        model -> Text,
+        language -> Text,
+        result -> Nullable<Text>,
+        error -> Nullable<Text>,
+        duration_ms -> Integer,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    model_options (id) {
+        id -> Integer,
+        model -> Text,
+        temperature -> Nullable<Float>,
+        top_p -> Nullable<Float>,
+        num_ctx -> Nullable<Integer>,
+        seed -> Nullable<Integer>,
+        system_prompt -> Text,
+    }
+}
+
 diesel::table! {
     phrases (id) {
         id -> Integer,
         original -> Text,
         pinyin -> Text,
+        simplified -> Text,
+        traditional -> Text,
+        hsk2_level -> Nullable<Integer>,
+        hsk3_level -> Nullable<Integer>,
+        document_line_id -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    prompt_templates (id) {
+        id -> Integer,
+        name -> Text,
+        template -> Text,
+    }
+}
+
+diesel::table! {
+    queue_items (id) {
+        id -> Integer,
+        text -> Text,
+        language -> Text,
+        status -> Text,
+        result -> Nullable<Text>,
+        error -> Nullable<Text>,
+        document_line_id -> Nullable<Integer>,
+        direction -> Text,
+        llm_model -> Text,
+    }
+}
+
+diesel::table! {
+    schedules (id) {
+        id -> Integer,
+        item -> Text,
+        exercise -> Text,
+        repetitions -> Integer,
+        interval_days -> Integer,
+        ease -> Float,
+        reviewed_at -> Text,
+    }
+}
+
+diesel::table! {
+    senses (id) {
+        id -> Integer,
+        phrase_id -> Integer,
+        position -> Integer,
         translation -> Text,
+        note -> Text,
+        language -> Text,
     }
 }
 
@@ -17,7 +165,61 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    strokes (id) {
+        id -> Integer,
+        character -> Text,
+        position -> Integer,
+        path -> Text,
+        median -> Text,
+    }
+}
+
+diesel::table! {
+    tone_answers (id) {
+        id -> Integer,
+        item -> Text,
+        expected -> Integer,
+        answered -> Nullable<Integer>,
+        previous_expected -> Nullable<Integer>,
+        previous_answered -> Nullable<Integer>,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    translations (id) {
+        id -> Integer,
+        phrase_id -> Integer,
+        language -> Text,
+        translation -> Text,
+    }
+}
+
+diesel::joinable!(document_lines -> documents (document_id));
+diesel::joinable!(examples -> phrases (phrase_id));
+diesel::joinable!(phrases -> document_lines (document_line_id));
+diesel::joinable!(queue_items -> document_lines (document_line_id));
+diesel::joinable!(senses -> phrases (phrase_id));
+diesel::joinable!(translations -> phrases (phrase_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
+    characters,
+    document_lines,
+    documents,
+    events,
+    examples,
+    exercise_results,
+    hsk_words,
+    lookups,
+    model_options,
     phrases,
+    prompt_templates,
+    queue_items,
+    schedules,
+    senses,
     settings,
+    strokes,
+    tone_answers,
+    translations,
 );
@@ -6,31 +6,130 @@ use crate::persistence::model;
 
 pub(crate) fn phrase(
     database_url: &str,
-    phrase_text: &str,
-    phrase_pinyin: &str,
+    new_phrase: &model::NewPhrase,
+    phrase_language: &str,
     phrase_translation: &str,
-) -> Result<usize, super::PersistenceError> {
-    use crate::persistence::schema::phrases::dsl::*;
+    phrase_senses: &[(String, String)],
+    save_event_kind: &str,
+) -> Result<bool, super::PersistenceError> {
+    use crate::persistence::schema::events;
+    use crate::persistence::schema::phrases;
+    use crate::persistence::schema::senses;
+    use crate::persistence::schema::translations;
 
     let mut conn = database_connection::create(database_url)?;
 
-    let new_phrase = model::NewPhrase {
-        original: phrase_text.to_owned(),
-        pinyin: phrase_pinyin.to_owned(),
-        translation: phrase_translation.to_owned(),
-    };
+    let phrase_text = new_phrase.original.as_str();
 
-    let result = diesel::insert_into(phrases::table())
-        .values(&new_phrase)
-        .on_conflict(original)
-        .do_update()
-        .set(&new_phrase)
-        .execute(&mut conn)
+    let result = conn
+        .transaction(|conn| {
+            let existing = phrases::table
+                .filter(phrases::original.eq(phrase_text))
+                .select(phrases::id)
+                .first::<i32>(conn)
+                .optional()?;
+
+            diesel::insert_into(phrases::table)
+                .values(new_phrase)
+                .on_conflict(phrases::original)
+                .do_update()
+                .set(new_phrase)
+                .execute(conn)?;
+
+            let phrase_id = phrases::table
+                .filter(phrases::original.eq(phrase_text))
+                .select(phrases::id)
+                .first::<i32>(conn)?;
+
+            let new_translation = model::NewTranslation {
+                phrase_id,
+                language: phrase_language.to_owned(),
+                translation: phrase_translation.to_owned(),
+            };
+
+            diesel::insert_into(translations::table)
+                .values(&new_translation)
+                .on_conflict((translations::phrase_id, translations::language))
+                .do_update()
+                .set(&new_translation)
+                .execute(conn)?;
+
+            let new_senses = phrase_senses
+                .iter()
+                .enumerate()
+                .map(|(position, (translation, note))| model::NewSense {
+                    phrase_id,
+                    position: position as i32,
+                    translation: translation.to_owned(),
+                    note: note.to_owned(),
+                    language: phrase_language.to_owned(),
+                })
+                .collect::<Vec<_>>();
+
+            diesel::delete(
+                senses::table
+                    .filter(senses::phrase_id.eq(phrase_id))
+                    .filter(senses::language.eq(phrase_language)),
+            )
+            .execute(conn)?;
+            diesel::insert_into(senses::table).values(&new_senses).execute(conn)?;
+
+            if existing.is_none() {
+                diesel::insert_into(events::table)
+                    .values(&model::NewEvent {
+                        kind: save_event_kind.to_owned(),
+                        item: phrase_text.to_owned(),
+                        duration_ms: 0,
+                    })
+                    .execute(conn)?;
+            }
+
+            Ok::<_, diesel::result::Error>(existing.is_none())
+        })
         .inspect_err(|error| log::error!("Failed to upsert phrase {}: {:?}", phrase_text, error))?;
 
     Ok(result)
 }
 
+pub(crate) fn example(
+    database_url: &str,
+    phrase_text: &str,
+    example_hanzi: &str,
+    example_pinyin: &str,
+    example_translation: &str,
+    example_language: &str,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::examples;
+    use crate::persistence::schema::phrases;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = conn
+        .transaction(|conn| {
+            let phrase_id = phrases::table
+                .filter(phrases::original.eq(phrase_text))
+                .select(phrases::id)
+                .first::<i32>(conn)?;
+
+            let new_example = model::NewExample {
+                phrase_id,
+                hanzi: example_hanzi.to_owned(),
+                pinyin: example_pinyin.to_owned(),
+                translation: example_translation.to_owned(),
+                language: example_language.to_owned(),
+            };
+
+            diesel::insert_into(examples::table)
+                .values(&new_example)
+                .on_conflict((examples::phrase_id, examples::hanzi))
+                .do_nothing()
+                .execute(conn)
+        })
+        .inspect_err(|error| log::error!("Failed to insert example for phrase {}: {:?}", phrase_text, error))?;
+
+    Ok(result)
+}
+
 pub(crate) fn setting(
     database_url: &str,
     setting_name: &str,
@@ -55,3 +154,483 @@ pub(crate) fn setting(
 
     Ok(result)
 }
+
+pub(crate) fn settings(
+    database_url: &str,
+    new_settings: &[(String, String)],
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::settings::dsl::*;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = conn
+        .transaction(|conn| {
+            new_settings.iter().try_fold(0, |count, (setting_name, setting_value)| {
+                let new_setting = model::NewSetting {
+                    name: setting_name.to_owned(),
+                    value: setting_value.to_owned(),
+                };
+                diesel::insert_into(settings::table())
+                    .values(&new_setting)
+                    .on_conflict(name)
+                    .do_update()
+                    .set(&new_setting)
+                    .execute(conn)
+                    .map(|upserted| count + upserted)
+            })
+        })
+        .inspect_err(|error| log::error!("Failed to upsert settings: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn prompt_template(
+    database_url: &str,
+    template_name: &str,
+    template_text: &str,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::prompt_templates::dsl::*;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_template = model::NewPromptTemplate {
+        name: template_name.to_owned(),
+        template: template_text.to_owned(),
+    };
+
+    let result = diesel::insert_into(prompt_templates::table())
+        .values(&new_template)
+        .on_conflict(name)
+        .do_update()
+        .set(&new_template)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to upsert prompt template {}: {:?}", template_name, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn model_options(
+    database_url: &str,
+    model_name: &str,
+    model_temperature: Option<f32>,
+    model_top_p: Option<f32>,
+    model_num_ctx: Option<i32>,
+    model_seed: Option<i32>,
+    model_system_prompt: &str,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::model_options;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_options = model::NewModelOptions {
+        model: model_name.to_owned(),
+        temperature: model_temperature,
+        top_p: model_top_p,
+        num_ctx: model_num_ctx,
+        seed: model_seed,
+        system_prompt: model_system_prompt.to_owned(),
+    };
+
+    let result = diesel::insert_into(model_options::table)
+        .values(&new_options)
+        .on_conflict(model_options::model)
+        .do_update()
+        .set(&new_options)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to upsert model options for {}: {:?}", model_name, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn queue_items(
+    database_url: &str,
+    items: &[(String, Option<i32>)],
+    item_language: &str,
+    item_direction: &str,
+    item_llm_model: &str,
+    item_status: &str,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::queue_items;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_items = items
+        .iter()
+        .map(|(item_text, item_line_id)| model::NewQueueItem {
+            text: item_text.to_owned(),
+            language: item_language.to_owned(),
+            status: item_status.to_owned(),
+            document_line_id: *item_line_id,
+            direction: item_direction.to_owned(),
+            llm_model: item_llm_model.to_owned(),
+        })
+        .collect::<Vec<_>>();
+
+    let result = diesel::insert_into(queue_items::table)
+        .values(&new_items)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to insert queue items: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn queue_item_status(
+    database_url: &str,
+    item_id: i32,
+    item_status: &str,
+    item_result: Option<&str>,
+    item_error: Option<&str>,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::queue_items;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::update(queue_items::table.filter(queue_items::id.eq(item_id)))
+        .set((
+            queue_items::status.eq(item_status),
+            queue_items::result.eq(item_result),
+            queue_items::error.eq(item_error),
+        ))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to update queue item {}: {:?}", item_id, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn queue_items_status(
+    database_url: &str,
+    from_status: &str,
+    to_status: &str,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::queue_items;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::update(queue_items::table.filter(queue_items::status.eq(from_status)))
+        .set(queue_items::status.eq(to_status))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to update queue items from {}: {:?}", from_status, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn lookup(
+    database_url: &str,
+    lookup_input: &str,
+    lookup_model: &str,
+    lookup_language: &str,
+    lookup_result: Option<&str>,
+    lookup_error: Option<&str>,
+    lookup_duration_ms: i32,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::lookups;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_lookup = model::NewLookup {
+        input: lookup_input.to_owned(),
+        model: lookup_model.to_owned(),
+        language: lookup_language.to_owned(),
+        result: lookup_result.map(str::to_owned),
+        error: lookup_error.map(str::to_owned),
+        duration_ms: lookup_duration_ms,
+    };
+
+    let result = diesel::insert_into(lookups::table)
+        .values(&new_lookup)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to insert lookup of {}: {:?}", lookup_input, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn characters(
+    database_url: &str,
+    new_characters: &[model::NewCharacter],
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::characters;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = conn
+        .transaction(|conn| {
+            new_characters.iter().try_fold(0, |count, new_character| {
+                diesel::insert_into(characters::table)
+                    .values(new_character)
+                    .on_conflict(characters::character)
+                    .do_update()
+                    .set(new_character)
+                    .execute(conn)
+                    .map(|inserted| count + inserted)
+            })
+        })
+        .inspect_err(|error| log::error!("Failed to import characters: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn strokes(database_url: &str, new_strokes: &[model::NewStroke]) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::strokes;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = conn
+        .transaction(|conn| {
+            new_strokes.iter().try_fold(0, |count, new_stroke| {
+                diesel::insert_into(strokes::table)
+                    .values(new_stroke)
+                    .on_conflict((strokes::character, strokes::position))
+                    .do_update()
+                    .set(new_stroke)
+                    .execute(conn)
+                    .map(|inserted| count + inserted)
+            })
+        })
+        .inspect_err(|error| log::error!("Failed to import strokes: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn stroke_counts(database_url: &str) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::characters;
+    use crate::persistence::schema::strokes;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::update(
+        characters::table
+            .filter(characters::stroke_count.is_null())
+            .filter(characters::character.eq_any(strokes::table.select(strokes::character))),
+    )
+    .set(characters::stroke_count.eq(diesel::dsl::sql::<
+        diesel::sql_types::Nullable<diesel::sql_types::Integer>,
+    >(
+        "(SELECT COUNT(*) FROM strokes WHERE strokes.character = characters.character)",
+    )))
+    .execute(&mut conn)
+    .inspect_err(|error| log::error!("Failed to update stroke counts: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn event(
+    database_url: &str,
+    event_kind: &str,
+    event_item: &str,
+    event_duration_ms: i32,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::events;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_event = model::NewEvent {
+        kind: event_kind.to_owned(),
+        item: event_item.to_owned(),
+        duration_ms: event_duration_ms,
+    };
+
+    let result = diesel::insert_into(events::table)
+        .values(&new_event)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to record {} event of {}: {:?}", event_kind, event_item, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn exercise_result(
+    database_url: &str,
+    result_exercise: &str,
+    result_item: &str,
+    result_correct: bool,
+    result_mistakes: i32,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::exercise_results;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_result = model::NewExerciseResult {
+        exercise: result_exercise.to_owned(),
+        item: result_item.to_owned(),
+        correct: result_correct,
+        mistakes: result_mistakes,
+    };
+
+    let result = diesel::insert_into(exercise_results::table)
+        .values(&new_result)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to insert exercise result for {}: {:?}", result_item, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn tone_answers(
+    database_url: &str,
+    new_answers: &[model::NewToneAnswer],
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::tone_answers;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::insert_into(tone_answers::table)
+        .values(new_answers)
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to insert tone answers: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn schedule(
+    database_url: &str,
+    schedule_item: &str,
+    schedule_exercise: &str,
+    schedule_repetitions: i32,
+    schedule_interval_days: i32,
+    schedule_ease: f32,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::schedules;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_schedule = model::NewSchedule {
+        item: schedule_item.to_owned(),
+        exercise: schedule_exercise.to_owned(),
+        repetitions: schedule_repetitions,
+        interval_days: schedule_interval_days,
+        ease: schedule_ease,
+    };
+
+    let result = diesel::insert_into(schedules::table)
+        .values(&new_schedule)
+        .on_conflict((schedules::item, schedules::exercise))
+        .do_update()
+        .set((
+            schedules::repetitions.eq(schedule_repetitions),
+            schedules::interval_days.eq(schedule_interval_days),
+            schedules::ease.eq(schedule_ease),
+            schedules::reviewed_at.eq(diesel::dsl::sql::<diesel::sql_types::Text>(
+                "datetime('now', 'localtime')",
+            )),
+        ))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to save schedule of {}: {:?}", schedule_item, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn phrase_scripts(
+    database_url: &str,
+    phrase_id: i32,
+    phrase_simplified: &str,
+    phrase_traditional: &str,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::phrases;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::update(phrases::table.filter(phrases::id.eq(phrase_id)))
+        .set((
+            phrases::simplified.eq(phrase_simplified),
+            phrases::traditional.eq(phrase_traditional),
+        ))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to update scripts of phrase {}: {:?}", phrase_id, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn hsk_words(database_url: &str, new_words: &[model::NewHskWord]) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::hsk_words;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = conn
+        .transaction(|conn| {
+            new_words.iter().try_fold(0, |count, new_word| {
+                diesel::insert_into(hsk_words::table)
+                    .values(new_word)
+                    .on_conflict((hsk_words::word, hsk_words::version))
+                    .do_nothing()
+                    .execute(conn)
+                    .map(|inserted| count + inserted)
+            })
+        })
+        .inspect_err(|error| log::error!("Failed to import HSK words: {:?}", error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn phrase_hsk_levels(
+    database_url: &str,
+    phrase_text: &str,
+    phrase_hsk2_level: Option<i32>,
+    phrase_hsk3_level: Option<i32>,
+) -> Result<usize, super::PersistenceError> {
+    use crate::persistence::schema::phrases;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let result = diesel::update(phrases::table.filter(phrases::original.eq(phrase_text)))
+        .set((
+            phrases::hsk2_level.eq(phrase_hsk2_level),
+            phrases::hsk3_level.eq(phrase_hsk3_level),
+        ))
+        .execute(&mut conn)
+        .inspect_err(|error| log::error!("Failed to update HSK levels of phrase {}: {:?}", phrase_text, error))?;
+
+    Ok(result)
+}
+
+pub(crate) fn document(
+    database_url: &str,
+    document_title: &str,
+    document_path: &str,
+    document_format: &str,
+    document_fingerprint: &str,
+    document_lines: &[(String, String)],
+) -> Result<i32, super::PersistenceError> {
+    use crate::persistence::schema::document_lines;
+    use crate::persistence::schema::documents;
+
+    let mut conn = database_connection::create(database_url)?;
+
+    let new_document = model::NewDocument {
+        title: document_title.to_owned(),
+        path: document_path.to_owned(),
+        format: document_format.to_owned(),
+        fingerprint: document_fingerprint.to_owned(),
+    };
+
+    let result = conn
+        .transaction(|conn| {
+            diesel::delete(documents::table.filter(documents::path.eq(document_path))).execute(conn)?;
+
+            diesel::insert_into(documents::table)
+                .values(&new_document)
+                .execute(conn)?;
+
+            let document_id = documents::table
+                .filter(documents::path.eq(document_path))
+                .select(documents::id)
+                .first::<i32>(conn)?;
+
+            let new_lines = document_lines
+                .iter()
+                .enumerate()
+                .map(|(position, (reference, text))| model::NewDocumentLine {
+                    document_id,
+                    position: position as i32,
+                    reference: reference.to_owned(),
+                    text: text.to_owned(),
+                })
+                .collect::<Vec<_>>();
+
+            diesel::insert_into(document_lines::table)
+                .values(&new_lines)
+                .execute(conn)?;
+
+            Ok::<_, diesel::result::Error>(document_id)
+        })
+        .inspect_err(|error| log::error!("Failed to import document {}: {:?}", document_path, error))?;
+
+    Ok(result)
+}
@@ -0,0 +1,210 @@
+use std::ops::Range;
+
+use crate::cjk;
+use crate::model;
+
+const INITIALS: [&str; 23] = [
+    "zh", "ch", "sh", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "r", "z", "c", "s", "y",
+    "w",
+];
+
+const FINALS: [&str; 37] = [
+    "a", "o", "e", "ai", "ei", "ao", "ou", "an", "en", "ang", "eng", "ong", "er", "i", "ia", "ie", "iao", "iu", "ian",
+    "in", "iang", "ing", "iong", "u", "ua", "uo", "uai", "ui", "uan", "un", "uang", "ueng", "ue", "v", "ve", "van",
+    "vn",
+];
+
+const ZERO_INITIAL_FINALS: [&str; 12] = ["a", "o", "e", "ai", "ei", "ao", "ou", "an", "en", "ang", "eng", "er"];
+
+const MARKED_VOWELS: [(&str, char); 6] = [
+    ("āáǎà", 'a'),
+    ("ēéěè", 'e'),
+    ("īíǐì", 'i'),
+    ("ōóǒò", 'o'),
+    ("ūúǔù", 'u'),
+    ("ǖǘǚǜ", 'v'),
+];
+
+const MAX_SYLLABLE_LENGTH: usize = 6;
+
+struct Letter {
+    range: Range<usize>,
+    base: char,
+    tone: Option<model::Tone>,
+}
+
+pub(crate) fn syllables(pinyin: &str) -> Vec<model::Syllable> {
+    let mut syllables = Vec::new();
+    let mut letters = Vec::new();
+    pinyin.char_indices().for_each(|(index, c)| match letter(c) {
+        Some((base, tone)) => letters.push(Letter {
+            range: index..index + c.len_utf8(),
+            base,
+            tone,
+        }),
+        None => {
+            let digit = c
+                .to_digit(10)
+                .filter(|digit| (1..=5).contains(digit))
+                .map(|digit| (index..index + 1, model::Tone::ALL[digit as usize - 1]));
+            split_word(&letters, digit, &mut syllables);
+            letters.clear();
+        }
+    });
+    split_word(&letters, None, &mut syllables);
+    syllables
+}
+
+pub(crate) fn tones(hanzi: &str, pinyin: &str) -> Option<Vec<model::Tone>> {
+    let tones = syllables(pinyin)
+        .into_iter()
+        .map(|syllable| syllable.tone)
+        .collect::<Vec<_>>();
+    (tones.len() == hanzi.chars().filter(|c| cjk::is_cjk(*c)).count()).then_some(tones)
+}
+
+pub(crate) fn ruby(hanzi: &str, pinyin: &str) -> Option<Vec<model::Ruby>> {
+    let syllables = syllables(pinyin);
+    if syllables.len() != hanzi.chars().filter(|c| cjk::is_cjk(*c)).count() {
+        return None;
+    }
+    let mut syllables = syllables.into_iter();
+    let mut tokens: Vec<model::Ruby> = Vec::new();
+    hanzi.chars().for_each(|c| {
+        if cjk::is_cjk(c) {
+            tokens.push(model::Ruby {
+                text: c.to_string(),
+                reading: syllables
+                    .next()
+                    .map(|syllable| (pinyin[syllable.range].to_owned(), syllable.tone)),
+            });
+        } else if let Some(token) = tokens.last_mut().filter(|token| token.reading.is_none()) {
+            token.text.push(c);
+        } else {
+            tokens.push(model::Ruby {
+                text: c.to_string(),
+                reading: None,
+            });
+        }
+    });
+    Some(tokens)
+}
+
+pub(crate) fn plain(syllable: &str) -> String {
+    syllable
+        .chars()
+        .filter_map(letter)
+        .map(|(base, _)| if base == 'v' { 'ü' } else { base })
+        .collect()
+}
+
+pub(crate) fn marked(numbered: &str) -> String {
+    numbered.split_whitespace().map(mark).collect::<Vec<_>>().join(" ")
+}
+
+fn mark(syllable: &str) -> String {
+    let syllable = syllable.replace("u:", "ü").replace("U:", "Ü");
+    let Some((letters, tone)) = syllable.char_indices().last().and_then(|(index, c)| {
+        c.to_digit(10)
+            .filter(|digit| (1..=5).contains(digit))
+            .map(|digit| (&syllable[..index], model::Tone::ALL[digit as usize - 1]))
+    }) else {
+        return syllable;
+    };
+    let lower = letters.to_lowercase();
+    let position = lower
+        .find('a')
+        .or_else(|| lower.find('e'))
+        .or_else(|| lower.find("ou"))
+        .or_else(|| lower.rfind(['i', 'o', 'u', 'ü']));
+    let Some(position) = position.filter(|_| tone != model::Tone::Neutral) else {
+        return letters.to_owned();
+    };
+    letters
+        .char_indices()
+        .map(|(index, c)| {
+            if index != position {
+                return c;
+            }
+            let base = match c.to_lowercase().next() {
+                Some('ü') => 'v',
+                other => other.unwrap_or(c),
+            };
+            MARKED_VOWELS
+                .iter()
+                .find(|(_, vowel)| *vowel == base)
+                .and_then(|(marked, _)| marked.chars().nth(tone.index()))
+                .map(|marked| {
+                    if c.is_uppercase() {
+                        marked.to_uppercase().next().unwrap_or(marked)
+                    } else {
+                        marked
+                    }
+                })
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+fn letter(c: char) -> Option<(char, Option<model::Tone>)> {
+    let c = c.to_lowercase().next()?;
+    match c {
+        'ü' => Some(('v', None)),
+        'a'..='z' => Some((c, None)),
+        _ => MARKED_VOWELS.iter().find_map(|(marked, base)| {
+            marked
+                .chars()
+                .position(|m| m == c)
+                .map(|position| (*base, Some(model::Tone::ALL[position])))
+        }),
+    }
+}
+
+fn split_word(letters: &[Letter], digit: Option<(Range<usize>, model::Tone)>, syllables: &mut Vec<model::Syllable>) {
+    let Some(parts) = split(letters, 0) else {
+        return;
+    };
+    let last = parts.len().saturating_sub(1);
+    parts.into_iter().enumerate().for_each(|(index, part)| {
+        let letters = &letters[part];
+        let marked = letters.iter().find_map(|letter| letter.tone);
+        let digit = digit.clone().filter(|_| index == last);
+        let start = letters[0].range.start;
+        let end = digit
+            .as_ref()
+            .map(|(range, _)| range.end)
+            .unwrap_or(letters[letters.len() - 1].range.end);
+        syllables.push(model::Syllable {
+            range: start..end,
+            tone: marked.or(digit.map(|(_, tone)| tone)).unwrap_or(model::Tone::Neutral),
+        });
+    });
+}
+
+fn split(letters: &[Letter], start: usize) -> Option<Vec<Range<usize>>> {
+    if start == letters.len() {
+        return Some(Vec::new());
+    }
+    (1..=MAX_SYLLABLE_LENGTH.min(letters.len() - start))
+        .rev()
+        .map(|length| start..start + length)
+        .filter(|part| is_syllable(&letters[part.clone()], start > 0 && part.end == letters.len()))
+        .find_map(|part| {
+            split(letters, part.end).map(|mut rest| {
+                rest.insert(0, part);
+                rest
+            })
+        })
+}
+
+fn is_syllable(letters: &[Letter], erhua: bool) -> bool {
+    let text = letters.iter().map(|letter| letter.base).collect::<String>();
+    let marks = letters.iter().filter(|letter| letter.tone.is_some()).count();
+    marks <= 1
+        && (ZERO_INITIAL_FINALS.contains(&text.as_str())
+            || INITIALS
+                .iter()
+                .filter_map(|initial| text.strip_prefix(initial))
+                .any(|rest| FINALS.contains(&rest))
+            || (erhua && text == "r"))
+}
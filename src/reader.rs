@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use crate::cjk;
+use crate::dictionary;
+use crate::model;
+
+const SENTENCE_ENDS: [char; 8] = ['。', '！', '？', '；', '!', '?', ';', '\n'];
+const CLOSING_MARKS: [char; 6] = ['”', '」', '』', '）', ')', '"'];
+
+pub(crate) fn segment(
+    text: &str,
+    dictionary: &dictionary::Dictionary,
+    known_words: &HashSet<String>,
+) -> (Vec<String>, Vec<model::Segment>) {
+    let max_length = known_words
+        .iter()
+        .map(|word| word.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max(dictionary.max_length());
+    let is_word = |word: &str| dictionary.contains(word) || known_words.contains(word);
+
+    let mut segments = Vec::new();
+    let mut line = 0;
+    let sentences = sentences(text);
+    sentences.iter().enumerate().for_each(|(sentence, text)| {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut start = 0;
+        while start < chars.len() {
+            let length = if cjk::is_cjk(chars[start]) {
+                (2..=max_length.min(chars.len() - start))
+                    .rev()
+                    .find(|length| {
+                        let word = &chars[start..start + length];
+                        word.iter().all(|c| cjk::is_cjk(*c)) && is_word(&word.iter().collect::<String>())
+                    })
+                    .unwrap_or(1)
+            } else {
+                chars[start..].iter().take_while(|c| !cjk::is_cjk(**c)).count()
+            };
+            segments.push(model::Segment {
+                text: chars[start..start + length].iter().collect(),
+                sentence,
+                line,
+                is_word: cjk::is_cjk(chars[start]),
+            });
+            start += length;
+        }
+        if text.ends_with('\n') {
+            line += 1;
+        }
+    });
+    (sentences, segments)
+}
+
+fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut sentence = String::new();
+    let mut ended = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        sentence.push(c);
+        ended |= SENTENCE_ENDS.contains(&c);
+        let continues = chars
+            .peek()
+            .is_some_and(|next| CLOSING_MARKS.contains(next) || (*next != '\n' && SENTENCE_ENDS.contains(next)));
+        if ended && !continues {
+            sentences.push(std::mem::take(&mut sentence));
+            ended = false;
+        }
+    }
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+    sentences
+}
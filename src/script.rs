@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::model;
+use crate::persistence;
+
+const OPENCC_DIR: &str = "opencc";
+const TO_TRADITIONAL_FILES: [&str; 2] = ["STCharacters.txt", "STPhrases.txt"];
+const TO_SIMPLIFIED_FILES: [&str; 2] = ["TSCharacters.txt", "TSPhrases.txt"];
+const TO_TRADITIONAL_TABLES: [&str; 2] = [
+    include_str!("../assets/opencc/STCharacters.txt"),
+    include_str!("../assets/opencc/STPhrases.txt"),
+];
+const TO_SIMPLIFIED_TABLES: [&str; 2] = [
+    include_str!("../assets/opencc/TSCharacters.txt"),
+    include_str!("../assets/opencc/TSPhrases.txt"),
+];
+
+#[derive(Default)]
+struct Table {
+    entries: HashMap<String, String>,
+    max_length: usize,
+}
+
+#[derive(Default)]
+pub(crate) struct Converter {
+    to_simplified: Table,
+    to_traditional: Table,
+}
+
+impl Table {
+    fn load(dir: &Path, files: &[&str]) -> Self {
+        let contents = files
+            .iter()
+            .filter_map(|file| {
+                std::fs::read_to_string(dir.join(file))
+                    .inspect_err(|err| log::info!("Conversion table {} not available: {}", file, err))
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        Table::parse(&contents.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+
+    fn parse(contents: &[&str]) -> Self {
+        let mut table = Table::default();
+        contents
+            .iter()
+            .flat_map(|content| content.lines())
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('\t'))
+            .filter_map(|(key, values)| values.split_whitespace().next().map(|value| (key, value)))
+            .for_each(|(key, value)| {
+                table.max_length = table.max_length.max(key.chars().count());
+                table.entries.insert(key.to_owned(), value.to_owned());
+            });
+        table
+    }
+
+    fn convert(&self, text: &str) -> String {
+        if self.entries.is_empty() {
+            return text.to_owned();
+        }
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut converted = String::with_capacity(text.len());
+        let mut start = 0;
+        while start < chars.len() {
+            let longest = (1..=self.max_length.min(chars.len() - start)).rev().find_map(|length| {
+                let key = chars[start..start + length].iter().collect::<String>();
+                self.entries.get(&key).map(|value| (length, value))
+            });
+            match longest {
+                Some((length, value)) => {
+                    converted.push_str(value);
+                    start += length;
+                }
+                None => {
+                    converted.push(chars[start]);
+                    start += 1;
+                }
+            }
+        }
+        converted
+    }
+}
+
+impl Converter {
+    pub(crate) fn load(database_url: &str) -> Self {
+        let dir = Path::new(database_url)
+            .parent()
+            .map(|data_dir| data_dir.join(OPENCC_DIR));
+        match dir {
+            Some(dir) if dir.exists() => {
+                log::info!("Using the conversion tables in {}", dir.display());
+                Converter {
+                    to_simplified: Table::load(&dir, &TO_SIMPLIFIED_FILES),
+                    to_traditional: Table::load(&dir, &TO_TRADITIONAL_FILES),
+                }
+            }
+            _ => Converter::bundled(),
+        }
+    }
+
+    fn bundled() -> Self {
+        Converter {
+            to_simplified: Table::parse(&TO_SIMPLIFIED_TABLES),
+            to_traditional: Table::parse(&TO_TRADITIONAL_TABLES),
+        }
+    }
+
+    pub(crate) fn is_available(&self) -> bool {
+        !self.to_simplified.entries.is_empty() && !self.to_traditional.entries.is_empty()
+    }
+
+    pub(crate) fn convert(&self, text: &str, script: model::Script) -> String {
+        match script {
+            model::Script::Simplified => self.to_simplified.convert(text),
+            model::Script::Traditional => self.to_traditional.convert(&self.to_simplified.convert(text)),
+        }
+    }
+}
+
+pub(crate) fn backfill(database_url: &str, converter: &Converter) {
+    if !converter.is_available() {
+        return;
+    }
+    match persistence::read::phrases_without_scripts(database_url) {
+        Ok(phrases) => phrases
+            .into_iter()
+            .map(|(id, original, simplified, traditional)| {
+                let scripts = (
+                    converter.convert(&original, model::Script::Simplified),
+                    converter.convert(&original, model::Script::Traditional),
+                );
+                (id, (simplified, traditional), scripts)
+            })
+            .filter(|(_, stored, scripts)| stored != scripts)
+            .for_each(|(id, _, (simplified, traditional))| {
+                if let Err(err) = persistence::write::phrase_scripts(database_url, id, &simplified, &traditional) {
+                    log::error!("Failed to store scripts of phrase {}: {}", id, err);
+                }
+            }),
+        Err(err) => log::error!("Failed to load phrases without scripts: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_with_the_bundled_tables() {
+        let converter = Converter::bundled();
+        assert!(converter.is_available());
+        assert_eq!(
+            converter.convert("头发干了以后去图书馆", model::Script::Traditional),
+            "頭髮乾了以後去圖書館"
+        );
+        assert_eq!(
+            converter.convert("這個系統的關係很複雜", model::Script::Simplified),
+            "这个系统的关系很复杂"
+        );
+    }
+}
@@ -1,6 +1,132 @@
+use std::str::FromStr;
+
+use crate::llm;
 use crate::model;
 use crate::persistence;
 
+pub(crate) struct Settings {
+    pub(crate) llm_model: String,
+    pub(crate) generation_options: model::GenerationOptions,
+    pub(crate) comparison_models: Vec<String>,
+    pub(crate) max_attempts: u32,
+    pub(crate) target_language: String,
+    pub(crate) preferred_script: model::Script,
+    pub(crate) hsk_version: model::HskVersion,
+    pub(crate) tts: model::TtsSettings,
+    pub(crate) tone_colors: model::ToneColors,
+    pub(crate) pinyin_layout: model::PinyinLayout,
+    pub(crate) daily_goals: model::DailyGoals,
+    pub(crate) clipboard: model::ClipboardSettings,
+    pub(crate) prompt_templates: model::PromptTemplates,
+}
+
+pub(crate) fn load(database_url: &str, default_llm_model: &str) -> Settings {
+    let llm_model = load_setting(database_url, model::SettingName::LlmModel).unwrap_or(default_llm_model.to_owned());
+
+    let generation_options = load_generation_options(database_url, &llm_model);
+
+    let comparison_models = load_setting(database_url, model::SettingName::ComparisonModels)
+        .map(|value| {
+            value
+                .split(',')
+                .filter(|llm_model| !llm_model.is_empty())
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let max_attempts = load_setting(database_url, model::SettingName::MaxAttempts)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(llm::DEFAULT_MAX_ATTEMPTS);
+
+    let target_language =
+        load_setting(database_url, model::SettingName::TargetLanguage).unwrap_or(model::DEFAULT_LANGUAGE.to_owned());
+
+    let preferred_script = load_setting(database_url, model::SettingName::PreferredScript)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default();
+
+    let hsk_version = load_setting(database_url, model::SettingName::HskVersion)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default();
+
+    let tts = model::TtsSettings {
+        backend: load_setting(database_url, model::SettingName::TtsBackend)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        command: load_setting(database_url, model::SettingName::TtsCommand)
+            .unwrap_or(model::DEFAULT_TTS_COMMAND.to_owned()),
+        url: load_setting(database_url, model::SettingName::TtsUrl).unwrap_or(model::DEFAULT_TTS_URL.to_owned()),
+    };
+
+    let tone_colors = model::ToneColors {
+        enabled: load_setting(database_url, model::SettingName::ToneColoring)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(true),
+        palette: load_setting(database_url, model::SettingName::ToneColors)
+            .ok()
+            .and_then(|value| model::ToneColors::parse_palette(&value))
+            .unwrap_or(model::PLECO_PALETTE),
+    };
+
+    let pinyin_layout = load_setting(database_url, model::SettingName::PinyinLayout)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default();
+
+    let default_goals = model::DailyGoals::default();
+    let load_goal = |setting_name, default| {
+        load_setting(database_url, setting_name)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    };
+    let daily_goals = model::DailyGoals {
+        new_phrases: load_goal(model::SettingName::DailyNewPhrases, default_goals.new_phrases),
+        reviews: load_goal(model::SettingName::DailyReviews, default_goals.reviews),
+        minutes: load_goal(model::SettingName::DailyMinutes, default_goals.minutes),
+    };
+
+    let clipboard = model::ClipboardSettings {
+        watch: load_setting(database_url, model::SettingName::ClipboardWatch)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        llm_fallback: load_setting(database_url, model::SettingName::ClipboardLlmFallback)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+        interval_secs: load_setting(database_url, model::SettingName::ClipboardInterval)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(model::DEFAULT_CLIPBOARD_INTERVAL_SECS),
+        ignore: load_setting(database_url, model::SettingName::ClipboardIgnore).unwrap_or_default(),
+    };
+
+    let prompt_templates = load_prompt_templates(database_url);
+
+    Settings {
+        llm_model,
+        generation_options,
+        comparison_models,
+        max_attempts,
+        target_language,
+        preferred_script,
+        hsk_version,
+        tts,
+        tone_colors,
+        pinyin_layout,
+        daily_goals,
+        clipboard,
+        prompt_templates,
+    }
+}
+
 pub(crate) fn load_setting(
     database_url: &str,
     setting_name: model::SettingName,
@@ -22,3 +148,31 @@ pub(crate) fn load_setting(
         })
         .map(|setting| setting.value)
 }
+
+pub(crate) fn load_prompt_templates(database_url: &str) -> model::PromptTemplates {
+    let mut templates = llm::default_templates();
+    match persistence::read::prompt_templates(database_url) {
+        Ok(stored) => stored
+            .into_iter()
+            .for_each(|stored| match model::PromptKind::from_str(&stored.name) {
+                Ok(kind) => templates.set(kind, stored.template),
+                Err(err) => log::warn!("Ignoring stored prompt template: {}", err),
+            }),
+        Err(err) => log::error!("Failed to load prompt templates, using defaults: {}", err),
+    }
+    templates
+}
+
+pub(crate) fn load_generation_options(database_url: &str, llm_model: &str) -> model::GenerationOptions {
+    match persistence::read::model_options(database_url, llm_model) {
+        Ok(options) => options.map(model::GenerationOptions::from).unwrap_or_default(),
+        Err(err) => {
+            log::error!(
+                "Failed to load options for model {}, using defaults: {}",
+                llm_model,
+                err
+            );
+            model::GenerationOptions::default()
+        }
+    }
+}
@@ -10,10 +10,38 @@ pub(crate) fn find(is_macos: bool) -> egui::KeyboardShortcut {
     egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::F)
 }
 
+pub(crate) fn examples(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::G)
+}
+
+pub(crate) fn history(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::R)
+}
+
+pub(crate) fn queue(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::U)
+}
+
+pub(crate) fn hsk_coverage(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::K)
+}
+
 pub(crate) fn exercise(is_macos: bool) -> egui::KeyboardShortcut {
     egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::X)
 }
 
+pub(crate) fn tone_drill(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::T)
+}
+
+pub(crate) fn reader(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::O)
+}
+
+pub(crate) fn statistics(is_macos: bool) -> egui::KeyboardShortcut {
+    egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::D)
+}
+
 pub(crate) fn settings(is_macos: bool) -> egui::KeyboardShortcut {
     egui::KeyboardShortcut::new(modifiers(is_macos), egui::Key::Comma)
 }
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use crate::model;
+use crate::persistence;
+
+pub(crate) const DAYS: i32 = 30;
+
+const HARDEST_ITEMS: usize = 10;
+
+pub(crate) fn load(database_url: &str) -> Result<model::Statistics, persistence::PersistenceError> {
+    let count_per_day = |days_ago: Vec<i32>| {
+        let mut counts = vec![0; DAYS as usize];
+        days_ago
+            .into_iter()
+            .filter(|days_ago| (0..DAYS).contains(days_ago))
+            .for_each(|days_ago| counts[(DAYS - 1 - days_ago) as usize] += 1);
+        counts
+    };
+    let per_day = |kind: model::EventKind| {
+        persistence::read::event_days(database_url, &kind.to_string(), DAYS).map(count_per_day)
+    };
+
+    let mut due_forecast = vec![0; DAYS as usize];
+    persistence::read::due_days(database_url, DAYS)?
+        .into_iter()
+        .for_each(|days_ahead| due_forecast[days_ahead.clamp(0, DAYS - 1) as usize] += 1);
+
+    let outcomes = persistence::read::exercise_outcomes(database_url)?;
+
+    let retention = model::ExerciseKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let recent = outcomes
+                .iter()
+                .filter(|(exercise, _, _, days_ago)| *exercise == kind.to_string() && *days_ago < DAYS)
+                .collect::<Vec<_>>();
+            let correct = recent.iter().filter(|(_, _, correct, _)| *correct).count();
+            (kind, recent.len(), correct)
+        })
+        .filter(|(_, total, _)| *total > 0)
+        .collect();
+
+    let mut items = HashMap::<&str, (usize, usize)>::new();
+    outcomes.iter().for_each(|(_, item, correct, _)| {
+        let (attempts, failures) = items.entry(item).or_default();
+        *attempts += 1;
+        if !correct {
+            *failures += 1;
+        }
+    });
+    let mut hardest = items
+        .into_iter()
+        .filter(|(_, (_, failures))| *failures > 0)
+        .map(|(item, (attempts, failures))| (item.to_owned(), attempts, failures))
+        .collect::<Vec<_>>();
+    hardest.sort_by(|a, b| (b.2 * a.1).cmp(&(a.2 * b.1)).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+    hardest.truncate(HARDEST_ITEMS);
+
+    Ok(model::Statistics {
+        saved_per_day: persistence::read::first_event_days(database_url, &model::EventKind::Save.to_string())
+            .map(count_per_day)?,
+        reviews_per_day: per_day(model::EventKind::Review)?,
+        lookups_per_day: per_day(model::EventKind::Lookup)?,
+        retention,
+        due_forecast,
+        hardest,
+    })
+}
@@ -1,12 +1,161 @@
+use std::time::Duration;
+
 use crate::model;
 use crate::persistence;
 
-impl From<persistence::model::Phrase> for model::Phrase {
-    fn from(value: persistence::model::Phrase) -> Self {
+impl From<persistence::model::StoredPhrase> for model::Phrase {
+    fn from((phrase, translation, mut senses, examples): persistence::model::StoredPhrase) -> Self {
+        senses.sort_by_key(|sense| sense.position);
         Self {
-            original: value.original,
+            original: phrase.original,
+            pinyin: phrase.pinyin,
+            language: translation
+                .as_ref()
+                .map(|translation| translation.language.clone())
+                .unwrap_or_default(),
+            translation: translation
+                .map(|translation| translation.translation)
+                .unwrap_or_default(),
+            senses: senses.into_iter().map(model::Sense::from).collect(),
+            examples: examples.into_iter().map(model::Example::from).collect(),
+            document_line: phrase.document_line_id,
+        }
+    }
+}
+
+impl From<persistence::model::Sense> for model::Sense {
+    fn from(value: persistence::model::Sense) -> Self {
+        Self {
+            translation: value.translation,
+            note: value.note,
+        }
+    }
+}
+
+impl From<persistence::model::Example> for model::Example {
+    fn from(value: persistence::model::Example) -> Self {
+        Self {
+            hanzi: value.hanzi,
             pinyin: value.pinyin,
             translation: value.translation,
         }
     }
 }
+
+impl From<persistence::model::ModelOptions> for model::GenerationOptions {
+    fn from(value: persistence::model::ModelOptions) -> Self {
+        Self {
+            temperature: value.temperature,
+            top_p: value.top_p,
+            num_ctx: value.num_ctx,
+            seed: value.seed,
+            system_prompt: value.system_prompt,
+        }
+    }
+}
+
+impl From<persistence::model::QueueItem> for model::QueueItem {
+    fn from(value: persistence::model::QueueItem) -> Self {
+        let status = value.status.parse().unwrap_or_else(|err| {
+            log::error!("Queue item {} has an invalid status: {}", value.id, err);
+            model::QueueStatus::Failed
+        });
+        let direction = value.direction.parse().unwrap_or_else(|err| {
+            log::error!("Queue item {} has an invalid direction: {}", value.id, err);
+            model::Direction::Detect
+        });
+        let result = value
+            .result
+            .and_then(|json| parse_phrase(&json, &value.language))
+            .map(|phrase| model::Phrase {
+                document_line: value.document_line_id,
+                ..phrase
+            });
+        Self {
+            id: value.id,
+            text: value.text,
+            language: value.language,
+            status,
+            result,
+            error: value.error,
+            direction,
+            llm_model: value.llm_model,
+        }
+    }
+}
+
+impl From<persistence::model::Document> for model::Document {
+    fn from(value: persistence::model::Document) -> Self {
+        let format = value.format.parse().unwrap_or_else(|err| {
+            log::error!("Document {} has an invalid format: {}", value.id, err);
+            model::DocumentFormat::Text
+        });
+        Self {
+            id: value.id,
+            title: value.title,
+            path: value.path,
+            format,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<persistence::model::DocumentLine> for model::DocumentLine {
+    fn from(value: persistence::model::DocumentLine) -> Self {
+        Self {
+            id: value.id,
+            document_id: value.document_id,
+            position: value.position as usize,
+            reference: value.reference,
+            text: value.text,
+        }
+    }
+}
+
+impl From<persistence::model::Schedule> for model::Schedule {
+    fn from(value: persistence::model::Schedule) -> Self {
+        Self {
+            repetitions: value.repetitions,
+            interval_days: value.interval_days,
+            ease: value.ease,
+        }
+    }
+}
+
+impl From<persistence::model::Lookup> for model::Lookup {
+    fn from(value: persistence::model::Lookup) -> Self {
+        Self {
+            input: value.input,
+            llm_model: value.model,
+            result: value.result.and_then(|json| parse_phrase(&json, &value.language)),
+            error: value.error,
+            duration: Duration::from_millis(value.duration_ms.max(0) as u64),
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<persistence::model::Character> for model::Character {
+    fn from(value: persistence::model::Character) -> Self {
+        Self {
+            character: value.character,
+            definition: value.definition,
+            pinyin: value.pinyin,
+            radical: value.radical,
+            components: Vec::new(),
+            stroke_count: value.stroke_count,
+            related: Vec::new(),
+            strokes: Vec::new(),
+        }
+    }
+}
+
+fn parse_phrase(json: &str, language: &str) -> Option<model::Phrase> {
+    serde_json::from_str::<model::Phrase>(json)
+        .inspect_err(|err| log::error!("Stored phrase could not be parsed: {}", err))
+        .ok()
+        .map(|phrase| model::Phrase {
+            language: language.to_owned(),
+            ..phrase
+        })
+}
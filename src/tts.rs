@@ -0,0 +1,233 @@
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use crate::model;
+
+const AUDIO_DIR: &str = "audio";
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+pub(crate) const PLAYBACK: bool = cfg!(feature = "audio");
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum TtsError {
+    #[error("Reading or writing audio failed")]
+    Io(#[from] std::io::Error),
+    #[error("TTS command failed: {0}")]
+    Command(String),
+    #[error("TTS server request failed")]
+    Http(#[from] reqwest::Error),
+    #[cfg(feature = "audio")]
+    #[error("Audio playback failed: {0}")]
+    Playback(String),
+    #[cfg(not(feature = "audio"))]
+    #[error("Hanzi was built without audio support")]
+    NoAudio,
+}
+
+pub(crate) trait SpeechBackend: Send + Sync {
+    fn id(&self) -> String;
+    fn synthesize(&self, text: &str, output: &Path) -> Result<(), TtsError>;
+}
+
+pub(crate) struct CommandBackend {
+    command: String,
+}
+
+pub(crate) struct HttpBackend {
+    url: String,
+}
+
+impl SpeechBackend for CommandBackend {
+    fn id(&self) -> String {
+        self.command.clone()
+    }
+
+    fn synthesize(&self, text: &str, output: &Path) -> Result<(), TtsError> {
+        let parts = shell_words::split(&self.command)
+            .map_err(|err| TtsError::Command(format!("{} is not a valid command: {}", self.command, err)))?;
+        let mut parts = parts.into_iter().map(|part| {
+            part.replace("{output}", &output.to_string_lossy())
+                .replace("{text}", text)
+        });
+        let program = parts
+            .next()
+            .ok_or_else(|| TtsError::Command("no command configured".to_string()))?;
+        let uses_stdin = !self.command.contains("{text}");
+
+        let mut child = Command::new(&program)
+            .args(parts)
+            .stdin(if uses_stdin { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| TtsError::Command(format!("{} could not be started: {}", program, err)))?;
+        if uses_stdin && let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let result = child.wait_with_output()?;
+        if result.status.success() {
+            Ok(())
+        } else {
+            Err(TtsError::Command(format!(
+                "{} exited with {}: {}",
+                program,
+                result.status,
+                String::from_utf8_lossy(&result.stderr).trim()
+            )))
+        }
+    }
+}
+
+impl SpeechBackend for HttpBackend {
+    fn id(&self) -> String {
+        self.url.clone()
+    }
+
+    fn synthesize(&self, text: &str, output: &Path) -> Result<(), TtsError> {
+        let url = self.url.replace("{text}", &percent_encode(text));
+        let audio = reqwest::blocking::get(url)?.error_for_status()?.bytes()?;
+        std::fs::write(output, audio)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn backend(settings: &model::TtsSettings) -> Arc<dyn SpeechBackend> {
+    match settings.backend {
+        model::TtsBackend::Command => Arc::new(CommandBackend {
+            command: settings.command.clone(),
+        }),
+        model::TtsBackend::Http => Arc::new(HttpBackend {
+            url: settings.url.clone(),
+        }),
+    }
+}
+
+pub(crate) fn cache_dir(database_url: &str) -> PathBuf {
+    Path::new(database_url)
+        .parent()
+        .map(|data_dir| data_dir.join(AUDIO_DIR))
+        .unwrap_or_else(|| PathBuf::from(AUDIO_DIR))
+}
+
+pub(crate) fn speak(backend: Arc<dyn SpeechBackend>, cache_dir: PathBuf, text: String) -> Result<(), TtsError> {
+    let audio = cached_audio(backend.as_ref(), &cache_dir, &text)?;
+    play(&audio)
+}
+
+pub(crate) fn cached_audio(backend: &dyn SpeechBackend, cache_dir: &Path, text: &str) -> Result<PathBuf, TtsError> {
+    let key = format!("{}\0{}", backend.id(), text);
+    let audio = cache_dir.join(format!("{:016x}.audio", fnv1a(key.as_bytes())));
+    if !audio.exists() {
+        std::fs::create_dir_all(cache_dir)?;
+        let partial = audio.with_extension("partial");
+        backend.synthesize(text, &partial)?;
+        std::fs::rename(&partial, &audio)?;
+        log::debug!("Cached audio of {} in {}", text, audio.display());
+    }
+    Ok(audio)
+}
+
+#[cfg(feature = "audio")]
+fn play(audio: &Path) -> Result<(), TtsError> {
+    let stream =
+        rodio::OutputStreamBuilder::open_default_stream().map_err(|err| TtsError::Playback(err.to_string()))?;
+    let file = std::fs::File::open(audio)?;
+    let sink = rodio::play(stream.mixer(), std::io::BufReader::new(file))
+        .map_err(|err| TtsError::Playback(err.to_string()))?;
+    sink.sleep_until_end();
+    Ok(())
+}
+
+#[cfg(not(feature = "audio"))]
+fn play(_audio: &Path) -> Result<(), TtsError> {
+    Err(TtsError::NoAudio)
+}
+
+/// FNV-1a, unlike the standard library's hashers its output is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    struct FakeBackend {
+        calls: AtomicUsize,
+    }
+
+    impl SpeechBackend for FakeBackend {
+        fn id(&self) -> String {
+            "fake".to_string()
+        }
+
+        fn synthesize(&self, text: &str, output: &Path) -> Result<(), TtsError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            std::fs::write(output, text)?;
+            Ok(())
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hanzi tts {} {}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn synthesizes_each_text_once() {
+        let backend = FakeBackend {
+            calls: AtomicUsize::new(0),
+        };
+        let dir = temp_dir("cache");
+
+        let first = cached_audio(&backend, &dir, "你好").unwrap();
+        let again = cached_audio(&backend, &dir, "你好").unwrap();
+        let other = cached_audio(&backend, &dir, "再见").unwrap();
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "你好");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cache_names_are_stable() {
+        assert_eq!(fnv1a(b""), FNV_OFFSET_BASIS);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn command_keeps_quoted_arguments_together() {
+        let backend = CommandBackend {
+            command: r#"sh -c 'cat > "$0"' {output}"#.to_string(),
+        };
+        let dir = temp_dir("command");
+
+        let audio = cached_audio(&backend, &dir, "谢谢").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&audio).unwrap(), "谢谢");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,11 +2,274 @@ use egui::Widget;
 use std::time::Duration;
 
 use crate::app;
+use crate::characters;
+use crate::cjk;
+use crate::model;
+use crate::pinyin;
+use crate::script;
 use crate::shortcuts;
+use crate::statistics;
+use crate::tts;
 
 impl eframe::App for app::HanziApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // CREATE UI
+        if self.open_queue {
+            self.show_queue_panel(ctx);
+        }
+
+        if self.open_history {
+            self.show_history_panel(ctx);
+        }
+
+        self.show_lookup_panel(ctx);
+
+        // HANDLE EVENTS
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::edit(self.is_macos))) {
+            self.edit();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::examples(self.is_macos))) {
+            self.generate_examples();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::exercise(self.is_macos))) {
+            self.learn();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::tone_drill(self.is_macos))) {
+            self.drill_tones();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::save(self.is_macos))) {
+            self.save_phrase();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::find(self.is_macos))) {
+            self.load_phrases();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::history(self.is_macos))) {
+            self.open_history = !self.open_history;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::queue(self.is_macos))) {
+            self.open_queue = !self.open_queue;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::hsk_coverage(self.is_macos))) {
+            self.show_hsk_coverage();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::reader(self.is_macos))) {
+            self.toggle_reader();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::statistics(self.is_macos))) {
+            self.show_statistics();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::settings(self.is_macos))) {
+            self.open_settings = !self.open_settings;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::about(self.is_macos))) {
+            self.open_about = !self.open_about;
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::F1)) {
+            self.open_help = !self.open_help;
+        }
+        let editing_multiline = ctx.memory(|m| {
+            [
+                "prompt_template_editor",
+                "system_prompt_editor",
+                "batch_editor",
+                "tone_drill_answer",
+                "quiz_answer",
+                "tts_editor",
+                "clipboard_ignore_editor",
+                "reader_path",
+                "reader_editor",
+            ]
+            .into_iter()
+            .any(|id| m.has_focus(egui::Id::new(id)))
+        });
+        if ctx.input(|i| i.key_pressed(egui::Key::Enter))
+            && !editing_multiline
+            && self.llm_queries.is_empty()
+            && self.examples_query.is_none()
+        {
+            self.query_llm();
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.handle_escape();
+        }
+
+        // HANDLE LLM QUERIES
+        if !self.llm_queries.is_empty() {
+            let mut pending = Vec::new();
+            std::mem::take(&mut self.llm_queries).into_iter().for_each(|query| {
+                let app::TrackedQuery {
+                    input,
+                    llm_model,
+                    promise,
+                    retry,
+                    start,
+                } = query;
+                match promise.try_take() {
+                    Ok(Ok(response)) => {
+                        self.record_lookup(&input, &llm_model, Ok(&response), start.elapsed());
+                        if self.comparing {
+                            self.candidates.push(app::Candidate {
+                                llm_model,
+                                result: Ok(response),
+                                duration: start.elapsed(),
+                            });
+                        } else {
+                            self.phrase_input = response.original.clone();
+                            self.phrase = Some(response);
+                        }
+                    }
+                    Ok(Err(err)) => {
+                        log::error!(
+                            "Error occurred when querying LLM {}: {} caused by {}",
+                            llm_model,
+                            err,
+                            err.cause()
+                        );
+                        self.record_lookup(&input, &llm_model, Err(&err.cause()), start.elapsed());
+                        if self.comparing {
+                            self.candidates.push(app::Candidate {
+                                llm_model,
+                                result: Err(err.cause()),
+                                duration: start.elapsed(),
+                            });
+                        } else {
+                            self.toasts
+                                .error(format!("Querying LLM failed: {}", err.cause()))
+                                .duration(Some(Duration::from_secs(5)))
+                                .show_progress_bar(true);
+                        }
+                    }
+                    Err(promise) => {
+                        if start.elapsed().as_secs() > 60 * retry.max_attempts as u64 {
+                            promise.abort();
+                            self.record_lookup(
+                                &input,
+                                &llm_model,
+                                Err(&"LLM query timed out".to_string()),
+                                start.elapsed(),
+                            );
+                            self.toasts
+                                .error(format!("LLM query to {} timed out", llm_model))
+                                .duration(Some(Duration::from_secs(5)))
+                                .show_progress_bar(true);
+                        } else {
+                            pending.push(app::TrackedQuery {
+                                input,
+                                llm_model,
+                                promise,
+                                retry,
+                                start,
+                            });
+                        }
+                    }
+                }
+            });
+            self.llm_queries = pending;
+            if self.llm_queries.is_empty() {
+                self.spinner.close();
+            }
+        }
+
+        self.poll_examples();
+        self.poll_queue_job();
+        self.process_queue();
+        if self.queue_job.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+        self.poll_speech_playback(ctx);
+        self.poll_template_validation();
+
+        if self.open_help {
+            self.show_help_window(ctx);
+        }
+
+        if self.open_about {
+            self.show_about_window(ctx);
+        }
+
+        if self.open_examples {
+            self.show_examples_window(ctx);
+        }
+
+        if self.open_character {
+            self.show_character_window(ctx);
+        }
+
+        if self.open_practice {
+            self.show_practice_window(ctx);
+        }
+
+        if self.open_exercises {
+            self.show_exercises_window(ctx);
+        }
+
+        if self.open_quiz {
+            self.show_quiz_window(ctx);
+        }
+
+        if self.open_tone_drill {
+            self.show_tone_drill_window(ctx);
+        }
+
+        if self.open_reader {
+            self.show_reader_window(ctx);
+        }
+
+        if self.open_statistics {
+            self.show_statistics_window(ctx);
+        }
+
+        if self.open_hsk_coverage {
+            self.show_hsk_coverage_window(ctx);
+        }
+
+        if self.open_batch {
+            self.show_batch_window(ctx);
+        }
+
+        if self.open_settings {
+            self.show_settings_window(ctx);
+        }
+
+        let progress = self
+            .llm_queries
+            .iter()
+            .map(|q| (q.llm_model.clone(), q.retry.clone()))
+            .chain(
+                self.examples_query
+                    .as_ref()
+                    .map(|_| (self.selected_llm_model.clone(), self.examples_retry.clone())),
+            )
+            .filter(|(_, retry)| self.comparing || retry.attempt() > 1)
+            .map(|(llm_model, retry)| {
+                format!(
+                    "{}: attempt {} of {}",
+                    llm_model,
+                    retry.attempt().max(1),
+                    retry.max_attempts
+                )
+            })
+            .collect::<Vec<_>>();
+        self.spinner.update_with_content(ctx, |ui| {
+            ui.label("Querying LLM...");
+            progress.iter().for_each(|line| {
+                ui.label(line);
+            });
+        });
+
+        self.track_study();
+        self.poll_clipboard(ctx);
+        self.poll_reader_gloss();
+        self.toasts.show(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.finish_study();
+    }
+}
+
+impl app::HanziApp {
+    fn show_lookup_panel(&mut self, ctx: &egui::Context) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical(|ui| {
                 ui.with_layout(
@@ -18,12 +281,129 @@ impl eframe::App for app::HanziApp {
                             .text_color(egui::Color32::YELLOW)
                             .margin(egui::Margin::same(16))
                             .font(egui::FontId::new(64., egui::FontFamily::Proportional))
-                            .hint_text("Enter Chinese text here and hit Enter")
+                            .hint_text("Enter Chinese or English text here and hit Enter")
                             .ui(ui)
                     },
                 );
+                ui.horizontal(|ui| {
+                    [
+                        model::Direction::Detect,
+                        model::Direction::FromChinese,
+                        model::Direction::IntoChinese,
+                    ]
+                    .into_iter()
+                    .for_each(|direction| {
+                        ui.selectable_value(
+                            &mut self.direction,
+                            direction,
+                            egui::RichText::new(direction.label(&self.target_language)).size(18.),
+                        );
+                    });
+                    if !self.hsk_index.is_empty() {
+                        ui.separator();
+                        egui::ComboBox::from_id_salt("hsk_filter")
+                            .selected_text(
+                                egui::RichText::new(match self.hsk_filter {
+                                    Some(level) => format!("{} level {}", self.hsk_version.label(), level),
+                                    None => "All levels".to_string(),
+                                })
+                                .size(18.),
+                            )
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut self.hsk_filter,
+                                    None,
+                                    egui::RichText::new("All levels").size(18.),
+                                );
+                                self.hsk_version.levels().for_each(|level| {
+                                    ui.selectable_value(
+                                        &mut self.hsk_filter,
+                                        Some(level),
+                                        egui::RichText::new(format!("{} level {}", self.hsk_version.label(), level))
+                                            .size(18.),
+                                    );
+                                });
+                            });
+                    }
+                    ui.separator();
+                    if ui
+                        .selectable_label(
+                            self.clipboard_settings.watch,
+                            egui::RichText::new("📋 Watch clipboard").size(18.),
+                        )
+                        .on_hover_text("Look up Chinese text as soon as it is copied")
+                        .clicked()
+                    {
+                        self.toggle_clipboard_watch(ctx);
+                    }
+                    if self.daily_goals.is_set() {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let met = self.daily_goals.is_met(&self.goal_progress);
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Today: {}/{} new · {}/{} reviews · {}/{} min · {} day streak",
+                                    self.goal_progress.new_phrases,
+                                    self.daily_goals.new_phrases,
+                                    self.goal_progress.reviews,
+                                    self.daily_goals.reviews,
+                                    self.studied_minutes(),
+                                    self.daily_goals.minutes,
+                                    self.goal_progress.streak
+                                ))
+                                .size(16.)
+                                .color(if met {
+                                    egui::Color32::LIGHT_GREEN
+                                } else {
+                                    egui::Color32::GRAY
+                                }),
+                            );
+                        });
+                    }
+                });
                 egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
                     if self.phrase.is_some() {
+                        let original = self.phrase.as_ref().map(|p| p.original.clone()).unwrap_or_default();
+                        let preferred = self.converter.convert(&original, self.preferred_script);
+                        let pinyin = self.phrase.as_ref().map(|p| p.pinyin.clone()).unwrap_or_default();
+                        let ruby = self.ruby(&preferred, &pinyin);
+                        ui.horizontal(|ui| {
+                            let clicked = match &ruby {
+                                Some(tokens) => {
+                                    ruby_line(ui, tokens, &self.tone_colors, ui.visuals().text_color(), 32.)
+                                }
+                                None => {
+                                    let colors = tone_colors(&self.tone_colors, &preferred, &pinyin);
+                                    character_buttons(ui, &preferred, 32., &colors)
+                                }
+                            };
+                            if let Some(character) = clicked {
+                                self.show_character(&character);
+                            }
+                            if speak_button(ui, self.speech_playback.is_some()) {
+                                self.speak(&original);
+                            }
+                            if let Some(line_id) = self.phrase.as_ref().and_then(|p| p.document_line)
+                                && source_button(ui)
+                            {
+                                self.open_phrase_source(line_id);
+                            }
+                        });
+                        other_script(ui, &self.converter, &original, self.preferred_script, 20.);
+                        let simplified = self.converter.convert(&original, model::Script::Simplified);
+                        ui.horizontal_wrapped(|ui| {
+                            hsk_badge(
+                                ui,
+                                self.hsk_version,
+                                self.hsk_index.phrase_level(&simplified, self.hsk_version),
+                            );
+                            let words = self.hsk_index.words(&simplified, self.hsk_version);
+                            if words.len() > 1 && self.hsk_index.level(&simplified, self.hsk_version).is_none() {
+                                words.into_iter().for_each(|(word, level)| {
+                                    ui.label(egui::RichText::new(word).size(20.));
+                                    hsk_badge(ui, self.hsk_version, level);
+                                });
+                            }
+                        });
                         ui.columns_const(|[col_1, col_2]| {
                             col_1.horizontal(|ui| {
                                 if self.edit_result {
@@ -36,13 +416,13 @@ impl eframe::App for app::HanziApp {
                                                 .ui(ui)
                                         },
                                     );
-                                } else if let Some(p) = &self.phrase {
-                                    ui.label(egui::RichText::new(p.pinyin.clone()).size(28.));
+                                } else if let Some(p) = self.phrase.as_ref().filter(|_| ruby.is_none()) {
+                                    pinyin_label(ui, &p.pinyin, &self.tone_colors, 28.);
                                 } else {
                                     ui.label("");
                                 }
                             });
-                            col_2.horizontal(|ui| {
+                            col_2.vertical(|ui| {
                                 if self.edit_result {
                                     ui.with_layout(
                                         egui::Layout::left_to_right(egui::Align::LEFT).with_main_justify(true),
@@ -53,28 +433,114 @@ impl eframe::App for app::HanziApp {
                                                 .ui(ui)
                                         },
                                     );
+                                    senses_editor(ui, &mut self.senses_input);
                                 } else if let Some(p) = &self.phrase {
-                                    ui.label(egui::RichText::new(p.translation.clone()).size(28.));
+                                    senses_list(ui, p);
                                 } else {
                                     ui.label("");
                                 }
                             });
                         });
+                        if let Some(p) = &self.phrase
+                            && !p.examples.is_empty()
+                        {
+                            ui.add_space(18.);
+                            ui.separator();
+                            examples_list(ui, &p.examples);
+                        }
                     } else if !self.phrases.is_empty() {
+                        let mut spoken = None;
+                        let mut source = None;
                         self.phrases.iter().for_each(|phrase| {
+                            let preferred = self.converter.convert(&phrase.original, self.preferred_script);
+                            let ruby = self.ruby(&preferred, &phrase.pinyin);
                             ui.columns_const(|[col_1, col_2, col_3]| {
-                                col_3
-                                    .vertical(|ui| ui.label(egui::RichText::new(phrase.translation.clone()).size(28.)));
-                                col_2.vertical(|ui| ui.label(egui::RichText::new(phrase.pinyin.clone()).size(28.)));
+                                col_3.vertical(|ui| senses_list(ui, phrase));
+                                if ruby.is_none() {
+                                    col_2.vertical(|ui| pinyin_label(ui, &phrase.pinyin, &self.tone_colors, 28.));
+                                }
                                 col_1.vertical_centered_justified(|ui| {
-                                    ui.label(
-                                        egui::RichText::new(phrase.original.clone())
-                                            .color(egui::Color32::YELLOW)
-                                            .size(44.),
-                                    )
+                                    if let Some(tokens) = &ruby {
+                                        ruby_line(ui, tokens, &self.tone_colors, egui::Color32::YELLOW, 44.);
+                                    } else {
+                                        let colors = tone_colors(&self.tone_colors, &preferred, &phrase.pinyin);
+                                        ui.label(hanzi_layout(&preferred, &colors, egui::Color32::YELLOW, 44.));
+                                    }
+                                    other_script(ui, &self.converter, &phrase.original, self.preferred_script, 18.);
+                                    ui.horizontal(|ui| {
+                                        hsk_badge(
+                                            ui,
+                                            self.hsk_version,
+                                            self.hsk_index.phrase_level(
+                                                &self.converter.convert(&phrase.original, model::Script::Simplified),
+                                                self.hsk_version,
+                                            ),
+                                        );
+                                        if speak_button(ui, self.speech_playback.is_some()) {
+                                            spoken = Some(phrase.original.clone());
+                                        }
+                                        if let Some(line_id) = phrase.document_line
+                                            && source_button(ui)
+                                        {
+                                            source = Some(line_id);
+                                        }
+                                    });
                                 });
                             });
                         });
+                        if let Some(text) = spoken {
+                            self.speak(&text);
+                        }
+                        if let Some(line_id) = source {
+                            self.open_phrase_source(line_id);
+                        }
+                    }
+                    if !self.candidates.is_empty() {
+                        ui.add_space(18.);
+                        ui.separator();
+                        let mut picked = None;
+                        let mut merged = None;
+                        ui.columns(self.candidates.len(), |columns| {
+                            columns.iter_mut().zip(self.candidates.iter()).enumerate().for_each(
+                                |(index, (ui, candidate))| {
+                                    ui.vertical(|ui| {
+                                        ui.label(egui::RichText::new(&candidate.llm_model).size(20.).strong());
+                                        ui.label(
+                                            egui::RichText::new(format!("{:.1} s", candidate.duration.as_secs_f32()))
+                                                .weak(),
+                                        );
+                                        match &candidate.result {
+                                            Ok(phrase) => {
+                                                ui.label(
+                                                    egui::RichText::new(phrase.original.clone())
+                                                        .color(egui::Color32::YELLOW)
+                                                        .size(32.),
+                                                );
+                                                ui.label(egui::RichText::new(phrase.pinyin.clone()).size(20.));
+                                                senses_list(ui, phrase);
+                                                ui.horizontal(|ui| {
+                                                    if ui.button(egui::RichText::new("Pick").size(20.)).clicked() {
+                                                        picked = Some(index);
+                                                    }
+                                                    if ui.button(egui::RichText::new("Merge").size(20.)).clicked() {
+                                                        merged = Some(index);
+                                                    }
+                                                });
+                                            }
+                                            Err(cause) => {
+                                                ui.label(egui::RichText::new(cause).color(egui::Color32::RED));
+                                            }
+                                        }
+                                    });
+                                },
+                            );
+                        });
+                        if let Some(index) = picked {
+                            self.pick_candidate(index);
+                        }
+                        if let Some(index) = merged {
+                            self.merge_candidate(index);
+                        }
                     }
                 });
                 ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
@@ -90,241 +556,2106 @@ impl eframe::App for app::HanziApp {
                 });
             });
         });
+    }
 
-        // HANDLE EVENTS
-        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::edit(self.is_macos))) {
-            self.edit();
-        }
-        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::exercise(self.is_macos))) {
-            self.learn();
-        }
-        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::save(self.is_macos))) {
-            self.save_phrase();
-        }
-        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::find(self.is_macos))) {
-            self.load_phrases();
-        }
-        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::settings(self.is_macos))) {
-            self.open_settings = !self.open_settings;
-        }
-        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts::about(self.is_macos))) {
-            self.open_about = !self.open_about;
-        }
-        if ctx.input(|i| i.key_pressed(egui::Key::F1)) {
-            self.open_help = !self.open_help;
-        }
-        if ctx.input(|i| i.key_pressed(egui::Key::Enter)) && self.llm_query.is_none() {
-            self.query_llm();
-        }
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            self.handle_escape();
-        }
-
-        // HANDLE LLM QUERIES
-        if let Some(query) = self.llm_query.take() {
+    fn poll_examples(&mut self) {
+        if let Some(query) = self.examples_query.take() {
             match query.try_take() {
-                Ok(Ok(response)) => {
-                    self.llm_query = None;
-                    self.llm_query_start = None;
+                Ok(Ok(examples)) => {
+                    self.examples_query = None;
+                    self.examples_query_start = None;
                     self.spinner.close();
-                    self.phrase_input = response.original.clone();
-                    self.phrase = Some(response);
+                    self.candidate_examples = examples;
                 }
                 Ok(Err(err)) => {
                     log::error!("Error occurred when querying LLM: {} caused by {}", err, err.cause());
-                    self.llm_query = None;
-                    self.llm_query_start = None;
+                    self.examples_query = None;
+                    self.examples_query_start = None;
                     self.spinner.close();
                     self.toasts
-                        .error(format!("Querying LLM failed: {}", err.cause()))
+                        .error(format!("Generating examples failed: {}", err.cause()))
                         .duration(Some(Duration::from_secs(5)))
                         .show_progress_bar(true);
                 }
                 Err(promise) => {
-                    if let Some(start) = self.llm_query_start {
-                        if start.elapsed().as_secs() > 60 {
+                    if let Some(start) = self.examples_query_start {
+                        if start.elapsed().as_secs() > 60 * self.examples_retry.max_attempts as u64 {
                             promise.abort();
-                            self.llm_query = None;
-                            self.llm_query_start = None;
+                            self.examples_query = None;
+                            self.examples_query_start = None;
                             self.spinner.close();
                             self.toasts
                                 .error("LLM query timed out")
                                 .duration(Some(Duration::from_secs(5)))
                                 .show_progress_bar(true);
                         } else {
-                            self.llm_query = Some(promise)
+                            self.examples_query = Some(promise)
                         }
                     } else {
-                        self.llm_query = Some(promise)
+                        self.examples_query = Some(promise)
                     }
                 }
             }
         }
+    }
 
-        if self.open_help {
-            egui::Window::new("Usage").auto_sized().show(ctx, |ui| {
-                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
-                    egui_extras::TableBuilder::new(ui)
-                        .column(egui_extras::Column::remainder().at_most(150.))
-                        .column(egui_extras::Column::remainder().at_most(400.))
-                        .body(|mut body| {
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("F1").size(20.).color(egui::Color32::YELLOW));
-                                });
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Opens this help dialog").size(20.));
-                                });
-                            });
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Enter").size(20.).color(egui::Color32::YELLOW));
-                                });
-                                row.col(|ui| {
-                                    ui.label(
-                                        egui::RichText::new("Translate the chinese text in the edit field").size(20.),
-                                    );
-                                });
-                            });
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(
-                                        egui::RichText::new(if self.is_macos { "Cmd+S" } else { "Ctrl+S" })
-                                            .size(20.)
-                                            .color(egui::Color32::YELLOW),
-                                    );
-                                });
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Save translation incl. pinyin").size(20.));
-                                });
-                            });
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(
-                                        egui::RichText::new(if self.is_macos { "Cmd+F" } else { "Ctrl+F" })
-                                            .size(20.)
-                                            .color(egui::Color32::YELLOW),
-                                    );
-                                });
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Find translation(s)").size(20.));
-                                });
-                            });
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(
-                                        egui::RichText::new(if self.is_macos { "Cmd+E" } else { "Ctrl+E" })
-                                            .size(20.)
-                                            .color(egui::Color32::YELLOW),
-                                    );
-                                });
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Edit translation").size(20.));
-                                });
-                            });
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(
-                                        egui::RichText::new(if self.is_macos { "Cmd+," } else { "Ctrl+," })
-                                            .size(20.)
-                                            .color(egui::Color32::YELLOW),
-                                    );
-                                });
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Open settings dialog").size(20.));
-                                });
-                            });
-                            body.row(20., |mut row| {
-                                row.col(|ui| {
-                                    ui.label(
-                                        egui::RichText::new(if self.is_macos { "Cmd+B" } else { "Ctrl+B" })
-                                            .size(20.)
-                                            .color(egui::Color32::YELLOW),
-                                    );
-                                });
-                                row.col(|ui| {
-                                    ui.label(egui::RichText::new("Open about dialog").size(20.));
-                                });
-                            });
-                        })
-                })
-            });
+    fn poll_queue_job(&mut self) {
+        if let Some(job) = self.queue_job.take() {
+            let app::QueueJob {
+                id,
+                llm_model,
+                promise,
+                start,
+            } = job;
+            match promise.try_take() {
+                Ok(Ok(response)) => self.complete_queue_job(id, &llm_model, Ok(response), start.elapsed()),
+                Ok(Err(err)) => {
+                    log::error!(
+                        "Error occurred when processing queue item {}: {} caused by {}",
+                        id,
+                        err,
+                        err.cause()
+                    );
+                    self.complete_queue_job(id, &llm_model, Err(err.cause()), start.elapsed());
+                }
+                Err(promise) => {
+                    if start.elapsed().as_secs() > 60 * self.max_attempts as u64 {
+                        promise.abort();
+                        self.complete_queue_job(
+                            id,
+                            &llm_model,
+                            Err("LLM query timed out".to_string()),
+                            start.elapsed(),
+                        );
+                    } else {
+                        self.queue_job = Some(app::QueueJob {
+                            id,
+                            llm_model,
+                            promise,
+                            start,
+                        });
+                    }
+                }
+            }
         }
+    }
 
-        if self.open_about {
-            egui::Window::new("About").auto_sized().show(ctx, |ui| {
-                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
-                    ui.vertical(|ui| {
-                        ui.label(egui::RichText::new("Hanzi").size(20.).color(egui::Color32::YELLOW));
-                        ui.label(egui::RichText::new("A little helper for Chinese learners").size(20.));
-                        ui.label(egui::RichText::new("Copyright (c) 2025, Gerd Reiss").size(20.));
-                        ui.horizontal(|ui| {
-                            ui.label(egui::RichText::new("Written In").size(20.));
-                            egui::Hyperlink::from_label_and_url(
-                                egui::RichText::new("Rust").size(20.),
-                                "https://www.rust-lang.org",
-                            )
-                            .ui(ui);
-                        });
-                        ui.horizontal(|ui| {
-                            ui.label(egui::RichText::new("Powered by").size(20.));
-                            egui::Hyperlink::from_label_and_url(
-                                egui::RichText::new("Egui").size(20.),
-                                "https://github.com/emilk/egui",
-                            )
-                            .ui(ui);
-                            ui.label(egui::RichText::new("and").size(20.));
-                            egui::Hyperlink::from_label_and_url(
-                                egui::RichText::new("Ollama").size(20.),
-                                "https://ollama.com/",
-                            )
-                            .ui(ui);
-                        });
-                        ui.horizontal(|ui| {
-                            ui.label(egui::RichText::new("Hosted on").size(20.));
-                            egui::Hyperlink::from_label_and_url(
-                                egui::RichText::new("Github").size(20.),
-                                "https://github.com/gerdreiss/hanzi",
-                            )
-                            .ui(ui);
-                        });
-                    });
-                });
-            });
+    fn poll_speech_playback(&mut self, ctx: &egui::Context) {
+        if let Some(playback) = self.speech_playback.take() {
+            match playback.try_take() {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    log::error!("Speech playback failed: {}", err);
+                    self.toasts
+                        .error(format!("Speech playback failed: {}", err))
+                        .duration(Some(Duration::from_secs(5)))
+                        .show_progress_bar(true);
+                }
+                Err(promise) => {
+                    self.speech_playback = Some(promise);
+                    ctx.request_repaint_after(Duration::from_millis(250));
+                }
+            }
         }
+    }
 
-        if self.open_settings {
-            egui::Window::new("Settings").auto_sized().show(ctx, |ui| {
-                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
-                    ui.vertical(|ui| {
-                        ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
-                            ui.label(egui::RichText::new("LLM model").size(20.));
-                            egui::ComboBox::from_label("")
-                                .selected_text(egui::RichText::new(&self.selected_llm_model).size(20.))
-                                .show_ui(ui, |ui| {
-                                    self.local_llm_models.clone().into_iter().for_each(|model| {
-                                        if ui
-                                            .selectable_value(
-                                                &mut self.selected_llm_model,
-                                                model.clone(),
-                                                egui::RichText::new(model.clone()).size(20.),
-                                            )
-                                            .changed()
+    fn poll_template_validation(&mut self) {
+        if let Some(validation) = self.template_validation.take() {
+            match validation.try_take() {
+                Ok(Ok(())) => {
+                    self.toasts
+                        .info("Prompt template is valid")
+                        .duration(Some(Duration::from_secs(5)))
+                        .show_progress_bar(true);
+                }
+                Ok(Err(err)) => {
+                    log::error!("Prompt template validation failed: {} caused by {}", err, err.cause());
+                    self.toasts
+                        .error(format!("Prompt template validation failed: {}", err.cause()))
+                        .duration(Some(Duration::from_secs(5)))
+                        .show_progress_bar(true);
+                }
+                Err(promise) => self.template_validation = Some(promise),
+            }
+        }
+    }
+
+    fn show_queue_panel(&mut self, ctx: &egui::Context) {
+        let mut reviewed = None;
+        let mut retried = None;
+        let mut removed = None;
+        egui::SidePanel::right("queue_panel")
+            .resizable(true)
+            .default_width(360.)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(12.).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new("Queue").size(24.).color(egui::Color32::YELLOW));
+                        ui.horizontal_wrapped(|ui| {
+                            if ui.button(egui::RichText::new("Add phrases").size(18.)).clicked() {
+                                self.open_batch = true;
+                            }
+                            if ui.button(egui::RichText::new("Save selected").size(18.)).clicked() {
+                                self.save_queue_selection();
+                            }
+                            if ui.button(egui::RichText::new("Clear saved").size(18.)).clicked() {
+                                self.clear_saved_queue_items();
+                            }
+                        });
+                        ui.separator();
+                        if self.queue.is_empty() {
+                            ui.label(egui::RichText::new("The queue is empty").size(18.));
+                        }
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            self.queue.iter().for_each(|item| {
+                                ui.horizontal(|ui| {
+                                    let mut selected = self.queue_selection.contains(&item.id);
+                                    if ui
+                                        .add_enabled(
+                                            item.status == model::QueueStatus::Done,
+                                            egui::Checkbox::without_text(&mut selected),
+                                        )
+                                        .changed()
+                                    {
+                                        if selected {
+                                            self.queue_selection.insert(item.id);
+                                        } else {
+                                            self.queue_selection.remove(&item.id);
+                                        }
+                                    }
+                                    ui.label(egui::RichText::new(&item.text).size(18.));
+                                    ui.label(egui::RichText::new(item.status.to_string()).color(match item.status {
+                                        model::QueueStatus::Pending => egui::Color32::GRAY,
+                                        model::QueueStatus::Running => egui::Color32::YELLOW,
+                                        model::QueueStatus::Done => egui::Color32::LIGHT_BLUE,
+                                        model::QueueStatus::Failed => egui::Color32::RED,
+                                        model::QueueStatus::Saved => egui::Color32::GREEN,
+                                    }));
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        if ui.small_button("Remove").clicked() {
+                                            removed = Some(item.id);
+                                        }
+                                        if item.status == model::QueueStatus::Failed
+                                            && ui.small_button("Retry").clicked()
                                         {
-                                            self.save_settings();
+                                            retried = Some(item.id);
+                                        }
+                                        if item.result.is_some() && ui.small_button("Review").clicked() {
+                                            reviewed = Some(item.id);
                                         }
                                     });
-                                })
-                        })
+                                });
+                                if let Some(error) = &item.error {
+                                    ui.label(egui::RichText::new(error).color(egui::Color32::RED).small());
+                                }
+                            });
+                        });
+                    });
+                });
+            });
+        if let Some(id) = reviewed {
+            self.review_queue_item(id);
+        }
+        if let Some(id) = retried {
+            self.retry_queue_item(id);
+        }
+        if let Some(id) = removed {
+            self.remove_queue_item(id);
+        }
+    }
+
+    fn show_history_panel(&mut self, ctx: &egui::Context) {
+        let mut reopened = None;
+        let mut saved = None;
+        let mut cleared = false;
+        egui::SidePanel::left("history_panel")
+            .resizable(true)
+            .default_width(320.)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(12.).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("History").size(24.).color(egui::Color32::YELLOW));
+                            if ui.button(egui::RichText::new("Clear").size(18.)).clicked() {
+                                cleared = true;
+                            }
+                        });
+                        self.average_durations().iter().for_each(|(llm_model, duration)| {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{}: {:.1} s on average",
+                                    llm_model,
+                                    duration.as_secs_f32()
+                                ))
+                                .weak(),
+                            );
+                        });
+                        ui.separator();
+                        if self.history.is_empty() {
+                            ui.label(egui::RichText::new("No lookups yet").size(18.));
+                        }
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            self.history.iter().enumerate().for_each(|(index, lookup)| {
+                                ui.horizontal(|ui| {
+                                    ui.label(egui::RichText::new(&lookup.input).size(18.));
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        if lookup.result.is_some() {
+                                            if ui.small_button("Save").clicked() {
+                                                saved = Some(index);
+                                            }
+                                            if ui.small_button("Open").clicked() {
+                                                reopened = Some(index);
+                                            }
+                                        }
+                                    });
+                                });
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{} · {} · {:.1} s",
+                                        lookup.created_at,
+                                        lookup.llm_model,
+                                        lookup.duration.as_secs_f32()
+                                    ))
+                                    .weak()
+                                    .small(),
+                                );
+                                if let Some(error) = &lookup.error {
+                                    ui.label(egui::RichText::new(error).color(egui::Color32::RED).small());
+                                }
+                                ui.separator();
+                            });
+                        });
+                    });
+                });
+            });
+        if let Some(index) = reopened {
+            self.reopen_lookup(index);
+        }
+        if let Some(index) = saved {
+            self.save_lookup(index);
+        }
+        if cleared {
+            self.clear_history();
+        }
+    }
+
+    fn show_help_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Usage").auto_sized().show(ctx, |ui| {
+            egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                egui_extras::TableBuilder::new(ui)
+                    .column(egui_extras::Column::remainder().at_most(150.))
+                    .column(egui_extras::Column::remainder().at_most(400.))
+                    .body(|mut body| {
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("F1").size(20.).color(egui::Color32::YELLOW));
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Opens this help dialog").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Enter").size(20.).color(egui::Color32::YELLOW));
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Translate the text in the edit field").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+S" } else { "Ctrl+S" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Save translation incl. pinyin").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+F" } else { "Ctrl+F" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Find translation(s)").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+E" } else { "Ctrl+E" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Edit translation").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+G" } else { "Ctrl+G" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Generate example sentences").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+X" } else { "Ctrl+X" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Choose an exercise").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+T" } else { "Ctrl+T" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Drill the tones of saved phrases").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+O" } else { "Ctrl+O" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Read a longer text word by word").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+D" } else { "Ctrl+D" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Show learning statistics").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+U" } else { "Ctrl+U" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Show the queue of pending translations").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+R" } else { "Ctrl+R" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Show the history of recent lookups").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+K" } else { "Ctrl+K" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Show how many HSK words are saved").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+," } else { "Ctrl+," })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Open settings dialog").size(20.));
+                            });
+                        });
+                        body.row(20., |mut row| {
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(if self.is_macos { "Cmd+B" } else { "Ctrl+B" })
+                                        .size(20.)
+                                        .color(egui::Color32::YELLOW),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(egui::RichText::new("Open about dialog").size(20.));
+                            });
+                        });
                     })
+            })
+        });
+    }
+
+    fn show_about_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("About").auto_sized().show(ctx, |ui| {
+            egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new("Hanzi").size(20.).color(egui::Color32::YELLOW));
+                    ui.label(egui::RichText::new("A little helper for Chinese learners").size(20.));
+                    ui.label(egui::RichText::new("Copyright (c) 2025, Gerd Reiss").size(20.));
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Written In").size(20.));
+                        egui::Hyperlink::from_label_and_url(
+                            egui::RichText::new("Rust").size(20.),
+                            "https://www.rust-lang.org",
+                        )
+                        .ui(ui);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Powered by").size(20.));
+                        egui::Hyperlink::from_label_and_url(
+                            egui::RichText::new("Egui").size(20.),
+                            "https://github.com/emilk/egui",
+                        )
+                        .ui(ui);
+                        ui.label(egui::RichText::new("and").size(20.));
+                        egui::Hyperlink::from_label_and_url(
+                            egui::RichText::new("Ollama").size(20.),
+                            "https://ollama.com/",
+                        )
+                        .ui(ui);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Hosted on").size(20.));
+                        egui::Hyperlink::from_label_and_url(
+                            egui::RichText::new("Github").size(20.),
+                            "https://github.com/gerdreiss/hanzi",
+                        )
+                        .ui(ui);
+                    });
                 });
             });
+        });
+    }
+
+    fn show_examples_window(&mut self, ctx: &egui::Context) {
+        let mut kept = None;
+        let mut discarded = None;
+        egui::Window::new("Example sentences").auto_sized().show(ctx, |ui| {
+            egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Number of examples").size(20.));
+                        ui.add(egui::DragValue::new(&mut self.examples_count).range(1..=10));
+                        if ui.button(egui::RichText::new("Generate").size(20.)).clicked() {
+                            self.generate_examples();
+                        }
+                    });
+                    ui.separator();
+                    if self.candidate_examples.is_empty() {
+                        ui.label(egui::RichText::new("No new examples").size(20.));
+                    }
+                    self.candidate_examples.iter().enumerate().for_each(|(index, example)| {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                ui.label(
+                                    egui::RichText::new(example.hanzi.clone())
+                                        .color(egui::Color32::YELLOW)
+                                        .size(24.),
+                                );
+                                ui.label(egui::RichText::new(example.pinyin.clone()).size(18.));
+                                ui.label(egui::RichText::new(example.translation.clone()).size(18.));
+                            });
+                            if ui.button(egui::RichText::new("Keep").size(20.)).clicked() {
+                                kept = Some(index);
+                            }
+                            if ui.button(egui::RichText::new("Discard").size(20.)).clicked() {
+                                discarded = Some(index);
+                            }
+                        });
+                        ui.separator();
+                    });
+                });
+            });
+        });
+        if let Some(index) = kept {
+            self.keep_example(index);
         }
+        if let Some(index) = discarded {
+            self.discard_example(index);
+        }
+    }
 
-        self.spinner.update_with_content(ctx, |ui| {
-            ui.label("Querying LLM...");
+    fn show_character_window(&mut self, ctx: &egui::Context) {
+        let mut selected = None;
+        if let Some(character) = &self.character {
+            egui::Window::new("Character")
+                .auto_sized()
+                .open(&mut self.open_character)
+                .show(ctx, |ui| {
+                    egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            if character.strokes.is_empty() {
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(&character.character)
+                                            .color(egui::Color32::YELLOW)
+                                            .size(96.),
+                                    );
+                                    if let Some(file) = characters::graphics_file(&self.database_url)
+                                        .filter(|file| !file.exists())
+                                    {
+                                        ui.label(
+                                            egui::RichText::new(format!(
+                                                "Place makemeahanzi's graphics.txt at {}\nand restart to see the stroke order",
+                                                file.display()
+                                            ))
+                                            .weak(),
+                                        );
+                                    }
+                                });
+                            } else {
+                                ui.vertical(|ui| {
+                                    let stroke_count = character.strokes.len();
+                                    self.stroke_animation.advance(ui.input(|i| i.stable_dt), stroke_count);
+                                    stroke_order(ui, &character.strokes, &self.stroke_animation, 240.);
+                                    ui.horizontal(|ui| {
+                                        if ui.button(egui::RichText::new("Replay").size(18.)).clicked() {
+                                            self.stroke_animation.replay();
+                                        }
+                                        if ui.button(egui::RichText::new("Back").size(18.)).clicked() {
+                                            self.stroke_animation.back();
+                                        }
+                                        let label = if self.stroke_animation.playing { "Pause" } else { "Play" };
+                                        if ui.button(egui::RichText::new(label).size(18.)).clicked() {
+                                            self.stroke_animation.toggle(stroke_count);
+                                        }
+                                        if ui.button(egui::RichText::new("Next").size(18.)).clicked() {
+                                            self.stroke_animation.forward(stroke_count);
+                                        }
+                                    });
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "Stroke {} of {}",
+                                            self.stroke_animation.step.min(stroke_count),
+                                            stroke_count
+                                        ))
+                                        .weak(),
+                                    );
+                                    if self.stroke_animation.playing {
+                                        ui.ctx().request_repaint();
+                                    }
+                                });
+                            }
+                            ui.vertical(|ui| {
+                                ui.label(egui::RichText::new(&character.pinyin).size(28.));
+                                ui.label(egui::RichText::new(&character.definition).size(20.));
+                                if let Some(stroke_count) = character.stroke_count {
+                                    ui.label(egui::RichText::new(format!("{} strokes", stroke_count)).size(20.));
+                                }
+                            });
+                        });
+                        ui.separator();
+                        egui::Grid::new("character_grid")
+                            .num_columns(2)
+                            .spacing([18., 8.])
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new("Radical").size(20.));
+                                if let Some(clicked) = character_buttons(ui, &character.radical, 24., &[]) {
+                                    selected = Some(clicked);
+                                }
+                                ui.end_row();
+                                ui.label(egui::RichText::new("Components").size(20.));
+                                if let Some(clicked) = character_buttons(ui, &character.components.concat(), 24., &[]) {
+                                    selected = Some(clicked);
+                                }
+                                ui.end_row();
+                                ui.label(egui::RichText::new("Related").size(20.));
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.set_max_width(480.);
+                                    if let Some(clicked) = character_buttons(ui, &character.related.concat(), 24., &[])
+                                    {
+                                        selected = Some(clicked);
+                                    }
+                                });
+                                ui.end_row();
+                            });
+                    });
+                });
+        }
+        if let Some(character) = selected {
+            self.show_character(&character);
+        }
+    }
+
+    fn show_practice_window(&mut self, ctx: &egui::Context) {
+        let mut finished = false;
+        let mut restarted = false;
+        let mut next = false;
+        egui::Window::new("Handwriting")
+            .auto_sized()
+            .open(&mut self.open_practice)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        if let Some(character) = self.practice.character() {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Character {} of {}",
+                                    self.practice.index + 1,
+                                    self.practice.characters.len()
+                                ))
+                                .weak(),
+                            );
+                            ui.label(egui::RichText::new(&character.pinyin).size(28.));
+                            ui.label(egui::RichText::new(&character.definition).size(20.));
+                        }
+                        finished = handwriting_canvas(ui, &mut self.practice, 320.);
+                        if self.practice.is_complete() {
+                            ui.label(
+                                egui::RichText::new(format!("Done with {} mistakes", self.practice.mistakes))
+                                    .size(20.)
+                                    .color(egui::Color32::GREEN),
+                            );
+                        } else if let Some(feedback) = self.practice.feedback {
+                            ui.label(egui::RichText::new(feedback.message()).size(20.).color(
+                                if feedback == model::StrokeFeedback::Correct {
+                                    egui::Color32::GREEN
+                                } else {
+                                    egui::Color32::RED
+                                },
+                            ));
+                        }
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.practice.hint, egui::RichText::new("Hint").size(18.));
+                            if ui.button(egui::RichText::new("Restart").size(18.)).clicked() {
+                                restarted = true;
+                            }
+                            if ui.button(egui::RichText::new("Next").size(18.)).clicked() {
+                                next = true;
+                            }
+                        });
+                    });
+                });
+            });
+        if finished {
+            self.finish_stroke();
+        }
+        if restarted {
+            self.restart_practice_character();
+        }
+        if next {
+            self.next_practice_character();
+        }
+    }
+
+    fn show_exercises_window(&mut self, ctx: &egui::Context) {
+        let mut started = None;
+        egui::Window::new("Exercises")
+            .auto_sized()
+            .open(&mut self.open_exercises)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    egui::Grid::new("exercises_grid")
+                        .num_columns(2)
+                        .spacing([18., 8.])
+                        .show(ui, |ui| {
+                            model::ExerciseKind::ALL.into_iter().for_each(|kind| {
+                                if ui.button(egui::RichText::new(kind.label()).size(20.)).clicked() {
+                                    started = Some(kind);
+                                }
+                                ui.label(egui::RichText::new(kind.description()).size(18.));
+                                ui.end_row();
+                            });
+                        });
+                });
+            });
+        if let Some(kind) = started {
+            self.start_exercise(kind);
+        }
+    }
+
+    fn show_quiz_window(&mut self, ctx: &egui::Context) {
+        let mut answered = None;
+        let mut checked = false;
+        let mut spoken = None;
+        let mut next = false;
+        egui::Window::new(self.quiz.kind.label())
+            .auto_sized()
+            .open(&mut self.open_quiz)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        let Some(card) = self.quiz.cards.get(self.quiz.index) else {
+                            return;
+                        };
+                        let phrase = &card.phrase;
+                        let hanzi = self.converter.convert(&phrase.original, self.preferred_script);
+                        ui.label(
+                            egui::RichText::new(format!("Phrase {} of {}", self.quiz.index + 1, self.quiz.cards.len()))
+                                .weak(),
+                        );
+                        match self.quiz.kind {
+                            model::ExerciseKind::Recognition => {
+                                ui.label(egui::RichText::new(&hanzi).color(egui::Color32::YELLOW).size(44.));
+                            }
+                            model::ExerciseKind::Recall => {
+                                senses_list(ui, phrase);
+                            }
+                            model::ExerciseKind::Listening => {
+                                ui.horizontal(|ui| {
+                                    pinyin_label(ui, &phrase.pinyin, &self.tone_colors, 28.);
+                                    if speak_button(ui, self.speech_playback.is_some()) {
+                                        spoken = Some(phrase.original.clone());
+                                    }
+                                });
+                            }
+                            model::ExerciseKind::Cloze => {
+                                if let Some(cloze) = &card.cloze {
+                                    ui.label(
+                                        egui::RichText::new(
+                                            self.converter.convert(&cloze.hanzi, self.preferred_script),
+                                        )
+                                        .color(egui::Color32::YELLOW)
+                                        .size(28.),
+                                    );
+                                    ui.label(egui::RichText::new(&cloze.translation).size(18.).weak());
+                                }
+                            }
+                            model::ExerciseKind::ToneDrill | model::ExerciseKind::Handwriting => {}
+                        }
+                        ui.add_space(12.);
+                        match self.quiz.kind {
+                            model::ExerciseKind::Recognition if !self.quiz.revealed => {
+                                if ui.button(egui::RichText::new("Show answer").size(18.)).clicked() {
+                                    self.quiz.revealed = true;
+                                }
+                            }
+                            model::ExerciseKind::Recognition => {
+                                pinyin_label(ui, &phrase.pinyin, &self.tone_colors, 24.);
+                                senses_list(ui, phrase);
+                                if self.quiz.result.is_none() {
+                                    ui.horizontal(|ui| {
+                                        if ui.button(egui::RichText::new("I knew it").size(18.)).clicked() {
+                                            answered = Some(true);
+                                        }
+                                        if ui.button(egui::RichText::new("I didn't").size(18.)).clicked() {
+                                            answered = Some(false);
+                                        }
+                                    });
+                                }
+                            }
+                            model::ExerciseKind::Listening => {
+                                ui.horizontal_wrapped(|ui| {
+                                    card.choices.iter().for_each(|choice| {
+                                        let text =
+                                            egui::RichText::new(self.converter.convert(choice, self.preferred_script))
+                                                .size(32.);
+                                        let text = match self.quiz.result {
+                                            Some(_) if *choice == phrase.original => text.color(egui::Color32::GREEN),
+                                            _ => text,
+                                        };
+                                        if ui
+                                            .add_enabled(self.quiz.result.is_none(), egui::Button::new(text))
+                                            .clicked()
+                                        {
+                                            answered = Some(*choice == phrase.original);
+                                        }
+                                    });
+                                });
+                            }
+                            _ => {
+                                let result = self.quiz.result;
+                                let response = egui::TextEdit::singleline(&mut self.quiz.answer)
+                                    .id(egui::Id::new("quiz_answer"))
+                                    .hint_text("Type the hanzi")
+                                    .font(egui::FontId::new(28., egui::FontFamily::Proportional))
+                                    .interactive(result.is_none())
+                                    .desired_width(400.)
+                                    .ui(ui);
+                                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                    checked = true;
+                                }
+                                if result.is_none() && ui.button(egui::RichText::new("Check").size(18.)).clicked() {
+                                    checked = true;
+                                }
+                            }
+                        }
+                        match self.quiz.result {
+                            Some(true) => {
+                                ui.label(egui::RichText::new("Correct").size(20.).color(egui::Color32::GREEN));
+                            }
+                            Some(false) => {
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label(egui::RichText::new("Wrong:").size(20.).color(egui::Color32::RED));
+                                    ui.label(egui::RichText::new(&hanzi).size(20.).color(egui::Color32::YELLOW));
+                                    pinyin_label(ui, &phrase.pinyin, &self.tone_colors, 20.);
+                                });
+                            }
+                            None => {}
+                        }
+                        if self.quiz.result.is_some() && ui.button(egui::RichText::new("Next").size(18.)).clicked() {
+                            next = true;
+                        }
+                    });
+                });
+            });
+        if let Some(correct) = answered {
+            self.answer_quiz(correct);
+        }
+        if checked {
+            self.check_quiz_answer();
+        }
+        if let Some(text) = spoken {
+            self.speak(&text);
+        }
+        if next {
+            self.next_quiz_card();
+        }
+    }
+
+    fn show_tone_drill_window(&mut self, ctx: &egui::Context) {
+        let mut checked = false;
+        let mut next = false;
+        egui::Window::new("Tone drill")
+            .auto_sized()
+            .open(&mut self.open_tone_drill)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        if let Some(phrase) = self.tone_drill.phrase() {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Phrase {} of {}",
+                                    self.tone_drill.index + 1,
+                                    self.tone_drill.phrases.len()
+                                ))
+                                .weak(),
+                            );
+                            ui.label(
+                                egui::RichText::new(self.converter.convert(&phrase.original, self.preferred_script))
+                                    .color(egui::Color32::YELLOW)
+                                    .size(44.),
+                            );
+                            ui.label(egui::RichText::new(&phrase.translation).size(20.).weak());
+                        }
+                        ui.label(egui::RichText::new("Type the pinyin with tone numbers, e.g. ni3 hao3").size(18.));
+                        let checked_answer = self.tone_drill.is_checked();
+                        let response = egui::TextEdit::singleline(&mut self.tone_drill.answer)
+                            .id(egui::Id::new("tone_drill_answer"))
+                            .font(egui::FontId::new(28., egui::FontFamily::Proportional))
+                            .interactive(!checked_answer)
+                            .desired_width(400.)
+                            .ui(ui);
+                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            checked = true;
+                        }
+                        if self.tone_drill.is_checked() {
+                            ui.horizontal_wrapped(|ui| {
+                                self.tone_drill.checks.iter().for_each(|check| {
+                                    let expected = format!("{}{}", check.expected, check.expected_tone.number());
+                                    if check.is_correct() {
+                                        ui.label(egui::RichText::new(expected).size(24.).color(egui::Color32::GREEN));
+                                    } else {
+                                        let answered = check
+                                            .answered
+                                            .as_ref()
+                                            .map(|(text, tone)| format!("{}{}", text, tone.number()))
+                                            .unwrap_or("–".to_string());
+                                        ui.label(egui::RichText::new(expected).size(24.).color(egui::Color32::RED));
+                                        ui.label(egui::RichText::new(format!("({})", answered)).size(18.).weak());
+                                    }
+                                });
+                            });
+                        }
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(
+                                    !self.tone_drill.is_checked(),
+                                    egui::Button::new(egui::RichText::new("Check").size(18.)),
+                                )
+                                .clicked()
+                            {
+                                checked = true;
+                            }
+                            if ui.button(egui::RichText::new("Next").size(18.)).clicked() {
+                                next = true;
+                            }
+                        });
+                        ui.separator();
+                        tone_statistics(ui, &self.tone_drill.statistics);
+                    });
+                });
+            });
+        if checked {
+            self.check_tone_drill();
+        }
+        if next {
+            self.next_tone_drill();
+        }
+    }
+
+    fn show_reader_window(&mut self, ctx: &egui::Context) {
+        let mut imported = false;
+        let mut opened = None;
+        let mut queued = None;
+        let mut deleted = None;
+        let mut scrolled = false;
+        let mut read = false;
+        let mut edited = false;
+        let mut selected = None;
+        let mut asked = false;
+        let mut saved = false;
+        let mut spoken = None;
+        egui::Window::new("Reader")
+            .default_size([900., 640.])
+            .resizable(true)
+            .open(&mut self.open_reader)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    if self.reader.segments.is_empty() {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("File").size(20.));
+                            egui::TextEdit::singleline(&mut self.reader.path)
+                                .id(egui::Id::new("reader_path"))
+                                .hint_text("Path of a TXT, EPUB, SRT or ASS file")
+                                .desired_width(500.)
+                                .ui(ui);
+                            if ui.button(egui::RichText::new("Import").size(18.)).clicked() {
+                                imported = true;
+                            }
+                        });
+                        if !self.reader.documents.is_empty() {
+                            egui::ScrollArea::vertical()
+                                .id_salt("reader_documents")
+                                .max_height(160.)
+                                .show(ui, |ui| {
+                                    egui::Grid::new("reader_documents_grid")
+                                        .num_columns(4)
+                                        .spacing([18., 8.])
+                                        .show(ui, |ui| {
+                                            self.reader.documents.iter().for_each(|document| {
+                                                ui.label(egui::RichText::new(&document.title).size(18.))
+                                                    .on_hover_text(&document.path);
+                                                ui.label(egui::RichText::new(document.format.label()).weak());
+                                                ui.label(egui::RichText::new(&document.created_at).weak());
+                                                ui.horizontal(|ui| {
+                                                    if ui.button(egui::RichText::new("Open").size(16.)).clicked() {
+                                                        opened = Some(document.id);
+                                                    }
+                                                    if ui.button(egui::RichText::new("Queue lines").size(16.)).clicked()
+                                                    {
+                                                        queued = Some(document.id);
+                                                    }
+                                                    if ui.button(egui::RichText::new("Delete").size(16.)).clicked() {
+                                                        deleted = Some(document.id);
+                                                    }
+                                                });
+                                                ui.end_row();
+                                            });
+                                        });
+                                });
+                        }
+                        ui.label(egui::RichText::new("or paste a text").size(18.));
+                        egui::ScrollArea::vertical()
+                            .id_salt("reader_input")
+                            .max_height(420.)
+                            .show(ui, |ui| {
+                                egui::TextEdit::multiline(&mut self.reader.text)
+                                    .id(egui::Id::new("reader_editor"))
+                                    .font(egui::FontId::new(20., egui::FontFamily::Proportional))
+                                    .desired_rows(12)
+                                    .desired_width(f32::INFINITY)
+                                    .ui(ui);
+                            });
+                        if ui
+                            .add_enabled(
+                                !self.reader.text.trim().is_empty(),
+                                egui::Button::new(egui::RichText::new("Read").size(18.)),
+                            )
+                            .clicked()
+                        {
+                            read = true;
+                        }
+                        return;
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button(egui::RichText::new("Edit text").size(18.)).clicked() {
+                            edited = true;
+                        }
+                        ui.separator();
+                        if let Some(document) = &self.reader.document {
+                            ui.label(egui::RichText::new(&document.title).size(18.).strong());
+                            ui.separator();
+                        }
+                        ui.label(egui::RichText::new("known").size(18.).color(egui::Color32::LIGHT_GREEN));
+                        ui.label(egui::RichText::new("unknown").size(18.));
+                    });
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .id_salt("reader_text")
+                        .max_height(400.)
+                        .show(ui, |ui| {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.spacing_mut().item_spacing.x = 0.;
+                                self.reader.segments.iter().enumerate().for_each(|(index, segment)| {
+                                    if !segment.is_word {
+                                        let text = segment.text.trim_end_matches('\n');
+                                        if !text.is_empty() {
+                                            ui.label(egui::RichText::new(text).size(24.));
+                                        }
+                                        if segment.text.ends_with('\n') {
+                                            ui.end_row();
+                                        }
+                                        return;
+                                    }
+                                    let mut text = egui::RichText::new(&segment.text).size(24.);
+                                    if self.reader.is_known(&segment.text) {
+                                        text = text.color(egui::Color32::LIGHT_GREEN);
+                                    }
+                                    if self.reader.selected == Some(index) {
+                                        text = text.background_color(egui::Color32::DARK_GRAY);
+                                    }
+                                    let response = ui
+                                        .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                        .on_hover_cursor(egui::CursorIcon::PointingHand);
+                                    if response.clicked() {
+                                        selected = Some(index);
+                                    }
+                                    if self.reader.scroll_to_selection && self.reader.selected == Some(index) {
+                                        response.scroll_to_me(Some(egui::Align::Center));
+                                        scrolled = true;
+                                    }
+                                });
+                            });
+                        });
+                    ui.separator();
+                    let Some(word) = self.reader.word() else {
+                        ui.label(egui::RichText::new("Click a word to look it up").size(18.).weak());
+                        return;
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(word).size(32.).color(egui::Color32::YELLOW));
+                        if let Some(gloss) = &self.reader.gloss {
+                            ui.label(egui::RichText::new(&gloss.pinyin).size(20.));
+                        }
+                        if speak_button(ui, self.speech_playback.is_some()) {
+                            spoken = Some(word.to_owned());
+                        }
+                    });
+                    match &self.reader.gloss {
+                        Some(gloss) => {
+                            ui.label(egui::RichText::new(&gloss.translation).size(20.));
+                            gloss.senses.iter().for_each(|sense| {
+                                let text = if sense.note.is_empty() {
+                                    sense.translation.clone()
+                                } else {
+                                    format!("{} ({})", sense.translation, sense.note)
+                                };
+                                ui.label(egui::RichText::new(text).size(16.).weak());
+                            });
+                        }
+                        None => {
+                            ui.label(egui::RichText::new("No dictionary entry").size(18.).weak());
+                        }
+                    }
+                    if let Some(sentence) = self.reader.sentence() {
+                        ui.label(egui::RichText::new(sentence).size(18.).italics());
+                    }
+                    if let (Some(document), Some(line)) = (&self.reader.document, self.reader.source_line()) {
+                        ui.label(egui::RichText::new(format!("{}, {}", document.title, line.reference)).weak());
+                    }
+                    ui.horizontal(|ui| {
+                        if self.reader.gloss_query.is_some() {
+                            ui.spinner();
+                            ui.label(egui::RichText::new("Asking the LLM...").size(18.));
+                        } else if ui.button(egui::RichText::new("Ask the LLM").size(18.)).clicked() {
+                            asked = true;
+                        }
+                        if ui
+                            .add_enabled(
+                                self.reader.gloss.is_some(),
+                                egui::Button::new(egui::RichText::new("Save with sentence").size(18.)),
+                            )
+                            .clicked()
+                        {
+                            saved = true;
+                        }
+                    });
+                });
+            });
+        if imported {
+            self.import_document();
+        }
+        if let Some(document_id) = opened {
+            self.open_document(document_id);
+        }
+        if let Some(document_id) = queued {
+            self.queue_document(document_id);
+        }
+        if let Some(document_id) = deleted {
+            self.delete_document(document_id);
+        }
+        if scrolled {
+            self.reader.scroll_to_selection = false;
+        }
+        if read {
+            self.read_text();
+        }
+        if edited {
+            self.edit_reader_text();
+        }
+        if let Some(index) = selected {
+            self.select_reader_word(index);
+        }
+        if asked {
+            self.ask_reader_gloss();
+        }
+        if saved {
+            self.save_reader_word();
+        }
+        if let Some(text) = spoken {
+            self.speak(&text);
+        }
+    }
+
+    fn show_statistics_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Statistics")
+            .auto_sized()
+            .open(&mut self.open_statistics)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    ui.columns_const(|[col_1, col_2]| {
+                        col_1.vertical(|ui| {
+                            ui.label(egui::RichText::new("Phrases saved per day").size(20.));
+                            bar_chart(
+                                ui,
+                                "saved_chart",
+                                &self.statistics.saved_per_day,
+                                1 - statistics::DAYS,
+                                egui::Color32::LIGHT_BLUE,
+                            );
+                            ui.label(egui::RichText::new("Reviews per day").size(20.));
+                            bar_chart(
+                                ui,
+                                "reviews_chart",
+                                &self.statistics.reviews_per_day,
+                                1 - statistics::DAYS,
+                                egui::Color32::GREEN,
+                            );
+                            ui.label(egui::RichText::new("Lookups per day").size(20.));
+                            bar_chart(
+                                ui,
+                                "lookups_chart",
+                                &self.statistics.lookups_per_day,
+                                1 - statistics::DAYS,
+                                egui::Color32::GRAY,
+                            );
+                            ui.label(egui::RichText::new("Reviews due").size(20.));
+                            bar_chart(ui, "due_chart", &self.statistics.due_forecast, 0, egui::Color32::YELLOW);
+                        });
+                        col_2.vertical(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!("Retention in the last {} days", statistics::DAYS))
+                                    .size(20.),
+                            );
+                            if self.statistics.retention.is_empty() {
+                                ui.label(egui::RichText::new("No exercises done yet").size(18.).weak());
+                            }
+                            egui::Grid::new("retention_grid")
+                                .num_columns(2)
+                                .spacing([18., 8.])
+                                .show(ui, |ui| {
+                                    self.statistics.retention.iter().for_each(|(kind, total, correct)| {
+                                        ui.label(
+                                            egui::RichText::new(format!("{}: {} of {}", kind.label(), correct, total))
+                                                .size(18.),
+                                        );
+                                        ui.add(
+                                            egui::ProgressBar::new(*correct as f32 / (*total).max(1) as f32)
+                                                .desired_width(160.)
+                                                .show_percentage(),
+                                        );
+                                        ui.end_row();
+                                    });
+                                });
+                            ui.add_space(12.);
+                            ui.label(egui::RichText::new("Hardest items").size(20.));
+                            egui::Grid::new("hardest_grid")
+                                .num_columns(2)
+                                .spacing([18., 8.])
+                                .show(ui, |ui| {
+                                    self.statistics.hardest.iter().for_each(|(item, attempts, failures)| {
+                                        ui.label(
+                                            egui::RichText::new(self.converter.convert(item, self.preferred_script))
+                                                .color(egui::Color32::YELLOW)
+                                                .size(24.),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(format!("failed {} of {} times", failures, attempts))
+                                                .size(18.),
+                                        );
+                                        ui.end_row();
+                                    });
+                                });
+                        });
+                    });
+                });
+            });
+    }
+
+    fn show_hsk_coverage_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new(format!("{} coverage", self.hsk_version.label()))
+            .auto_sized()
+            .open(&mut self.open_hsk_coverage)
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    egui::Grid::new("hsk_coverage_grid")
+                        .num_columns(2)
+                        .spacing([18., 8.])
+                        .show(ui, |ui| {
+                            self.hsk_coverage.iter().for_each(|(level, total, saved)| {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "You have saved {} of {} HSK{} words",
+                                        saved, total, level
+                                    ))
+                                    .size(20.),
+                                );
+                                ui.add(
+                                    egui::ProgressBar::new(*saved as f32 / (*total).max(1) as f32)
+                                        .desired_width(240.)
+                                        .show_percentage(),
+                                );
+                                ui.end_row();
+                            });
+                        });
+                });
+            });
+    }
+
+    fn show_batch_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Add phrases to the queue")
+            .auto_sized()
+            .show(ctx, |ui| {
+                egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new("One phrase per line").size(20.));
+                        egui::TextEdit::multiline(&mut self.batch_input)
+                            .id(egui::Id::new("batch_editor"))
+                            .font(egui::FontId::new(20., egui::FontFamily::Proportional))
+                            .desired_rows(10)
+                            .desired_width(400.)
+                            .ui(ui);
+                        ui.horizontal(|ui| {
+                            if ui.button(egui::RichText::new("Enqueue").size(20.)).clicked() {
+                                self.enqueue_batch();
+                            }
+                            if ui.button(egui::RichText::new("Cancel").size(20.)).clicked() {
+                                self.open_batch = false;
+                            }
+                        });
+                    });
+                });
+            });
+    }
+
+    fn show_settings_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Settings").auto_sized().show(ctx, |ui| {
+            egui::Frame::NONE.inner_margin(18.).show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("LLM model").size(20.));
+                        egui::ComboBox::from_label("")
+                            .selected_text(egui::RichText::new(&self.selected_llm_model).size(20.))
+                            .show_ui(ui, |ui| {
+                                self.local_llm_models.clone().into_iter().for_each(|model| {
+                                    if ui
+                                        .selectable_value(
+                                            &mut self.selected_llm_model,
+                                            model.clone(),
+                                            egui::RichText::new(model.clone()).size(20.),
+                                        )
+                                        .changed()
+                                    {
+                                        self.change_llm_model();
+                                    }
+                                });
+                            });
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Compare with").size(20.));
+                        self.local_llm_models
+                            .clone()
+                            .into_iter()
+                            .filter(|llm_model| *llm_model != self.selected_llm_model)
+                            .collect::<Vec<_>>()
+                            .into_iter()
+                            .for_each(|llm_model| {
+                                let mut compare = self.comparison_models.contains(&llm_model);
+                                if ui
+                                    .checkbox(&mut compare, egui::RichText::new(&llm_model).size(20.))
+                                    .changed()
+                                {
+                                    self.toggle_comparison_model(&llm_model);
+                                }
+                            });
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Maximum attempts per query").size(20.));
+                        let response = ui.add(egui::DragValue::new(&mut self.max_attempts).range(1..=10));
+                        if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                            self.save_settings();
+                        }
+                    });
+                    egui::CollapsingHeader::new(egui::RichText::new("Model options").size(20.))
+                        .id_salt("model_options")
+                        .show(ui, |ui| {
+                            egui::Grid::new("model_options_grid").num_columns(2).show(ui, |ui| {
+                                optional_value(
+                                    ui,
+                                    "Temperature",
+                                    &mut self.generation_options.temperature,
+                                    0.8,
+                                    0.0..=2.0,
+                                    0.01,
+                                );
+                                optional_value(ui, "Top p", &mut self.generation_options.top_p, 0.9, 0.0..=1.0, 0.01);
+                                optional_value(
+                                    ui,
+                                    "Context size",
+                                    &mut self.generation_options.num_ctx,
+                                    4096,
+                                    512..=131072,
+                                    256.,
+                                );
+                                optional_value(ui, "Seed", &mut self.generation_options.seed, 42, 0..=i32::MAX, 1.);
+                            });
+                            ui.label(egui::RichText::new("System prompt").size(20.));
+                            egui::TextEdit::multiline(&mut self.generation_options.system_prompt)
+                                .id(egui::Id::new("system_prompt_editor"))
+                                .desired_rows(3)
+                                .desired_width(600.)
+                                .ui(ui);
+                            if ui.button(egui::RichText::new("Save model options").size(20.)).clicked() {
+                                self.save_generation_options();
+                            }
+                        });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Target language").size(20.));
+                        egui::ComboBox::from_id_salt("target_language")
+                            .selected_text(egui::RichText::new(&self.target_language).size(20.))
+                            .show_ui(ui, |ui| {
+                                model::LANGUAGES.into_iter().for_each(|language| {
+                                    if ui
+                                        .selectable_value(
+                                            &mut self.target_language,
+                                            language.to_owned(),
+                                            egui::RichText::new(language).size(20.),
+                                        )
+                                        .changed()
+                                    {
+                                        self.change_target_language();
+                                    }
+                                });
+                            });
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("HSK version").size(20.));
+                        egui::ComboBox::from_id_salt("hsk_version")
+                            .selected_text(egui::RichText::new(self.hsk_version.label()).size(20.))
+                            .show_ui(ui, |ui| {
+                                model::HskVersion::ALL.into_iter().for_each(|version| {
+                                    if ui
+                                        .selectable_value(
+                                            &mut self.hsk_version,
+                                            version,
+                                            egui::RichText::new(version.label()).size(20.),
+                                        )
+                                        .changed()
+                                    {
+                                        self.hsk_filter = None;
+                                        self.save_settings();
+                                    }
+                                });
+                            });
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Preferred script").size(20.));
+                        egui::ComboBox::from_id_salt("preferred_script")
+                            .selected_text(egui::RichText::new(self.preferred_script.label()).size(20.))
+                            .show_ui(ui, |ui| {
+                                model::Script::ALL.into_iter().for_each(|script| {
+                                    if ui
+                                        .selectable_value(
+                                            &mut self.preferred_script,
+                                            script,
+                                            egui::RichText::new(script.label()).size(20.),
+                                        )
+                                        .changed()
+                                    {
+                                        self.save_settings();
+                                    }
+                                });
+                            });
+                    });
+                    if tts::PLAYBACK {
+                        ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                            ui.label(egui::RichText::new("Speech").size(20.));
+                            egui::ComboBox::from_id_salt("tts_backend")
+                                .selected_text(egui::RichText::new(self.tts_settings.backend.label()).size(20.))
+                                .show_ui(ui, |ui| {
+                                    model::TtsBackend::ALL.into_iter().for_each(|backend| {
+                                        ui.selectable_value(
+                                            &mut self.tts_settings.backend,
+                                            backend,
+                                            egui::RichText::new(backend.label()).size(20.),
+                                        );
+                                    });
+                                });
+                            let template = match self.tts_settings.backend {
+                                model::TtsBackend::Command => &mut self.tts_settings.command,
+                                model::TtsBackend::Http => &mut self.tts_settings.url,
+                            };
+                            egui::TextEdit::singleline(template)
+                                .id(egui::Id::new("tts_editor"))
+                                .desired_width(400.)
+                                .ui(ui)
+                                .on_hover_text("Placeholders: {text}, and {output} for commands");
+                            if ui.button(egui::RichText::new("Save").size(20.)).clicked() {
+                                self.change_tts_settings();
+                            }
+                        });
+                    }
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Daily goals").size(20.));
+                        let mut changed = false;
+                        [
+                            (&mut self.daily_goals.new_phrases, "new phrases"),
+                            (&mut self.daily_goals.reviews, "reviews"),
+                            (&mut self.daily_goals.minutes, "minutes"),
+                        ]
+                        .into_iter()
+                        .for_each(|(goal, label)| {
+                            let response = ui.add(egui::DragValue::new(goal).range(0..=500));
+                            changed |= response.drag_stopped() || (response.changed() && !response.dragged());
+                            ui.label(egui::RichText::new(label).size(18.));
+                        });
+                        if changed {
+                            self.save_settings();
+                            self.reload_goal_progress();
+                        }
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Clipboard").size(20.));
+                        let mut changed = false;
+                        if ui
+                            .checkbox(
+                                &mut self.clipboard_settings.watch,
+                                egui::RichText::new("watch").size(18.),
+                            )
+                            .changed()
+                        {
+                            self.watch_clipboard(ctx);
+                            changed = true;
+                        }
+                        changed |= ui
+                            .checkbox(
+                                &mut self.clipboard_settings.llm_fallback,
+                                egui::RichText::new("ask the LLM if the dictionary has no entry").size(18.),
+                            )
+                            .changed();
+                        ui.label(egui::RichText::new("at most every").size(18.));
+                        let response =
+                            ui.add(egui::DragValue::new(&mut self.clipboard_settings.interval_secs).range(1..=60));
+                        changed |= response.drag_stopped() || (response.changed() && !response.dragged());
+                        ui.label(egui::RichText::new("seconds").size(18.));
+                        if changed {
+                            self.save_settings();
+                        }
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Ignore copied text").size(20.));
+                        egui::TextEdit::multiline(&mut self.clipboard_settings.ignore)
+                            .id(egui::Id::new("clipboard_ignore_editor"))
+                            .hint_text("One phrase per line")
+                            .desired_rows(3)
+                            .desired_width(400.)
+                            .ui(ui);
+                        if ui.button(egui::RichText::new("Save").size(20.)).clicked() {
+                            self.save_settings();
+                        }
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Pinyin").size(20.));
+                        egui::ComboBox::from_id_salt("pinyin_layout")
+                            .selected_text(egui::RichText::new(self.pinyin_layout.label()).size(20.))
+                            .show_ui(ui, |ui| {
+                                model::PinyinLayout::ALL.into_iter().for_each(|layout| {
+                                    if ui
+                                        .selectable_value(
+                                            &mut self.pinyin_layout,
+                                            layout,
+                                            egui::RichText::new(layout.label()).size(20.),
+                                        )
+                                        .changed()
+                                    {
+                                        self.save_settings();
+                                    }
+                                });
+                            });
+                    });
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        let mut changed = ui
+                            .checkbox(
+                                &mut self.tone_colors.enabled,
+                                egui::RichText::new("Tone colours").size(20.),
+                            )
+                            .changed();
+                        ui.add_enabled_ui(self.tone_colors.enabled, |ui| {
+                            model::Tone::ALL.into_iter().for_each(|tone| {
+                                ui.label(egui::RichText::new(tone.label()).size(18.));
+                                changed |= ui
+                                    .color_edit_button_srgb(&mut self.tone_colors.palette[tone.index()])
+                                    .changed();
+                            });
+                            if ui.button(egui::RichText::new("Pleco").size(18.)).clicked() {
+                                self.tone_colors.palette = model::PLECO_PALETTE;
+                                changed = true;
+                            }
+                            if ui.button(egui::RichText::new("MDBG").size(18.)).clicked() {
+                                self.tone_colors.palette = model::MDBG_PALETTE;
+                                changed = true;
+                            }
+                        });
+                        if changed {
+                            self.save_settings();
+                        }
+                    });
+                    ui.separator();
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
+                        ui.label(egui::RichText::new("Prompt template").size(20.));
+                        egui::ComboBox::from_id_salt("prompt_template")
+                            .selected_text(egui::RichText::new(self.selected_prompt_kind.label()).size(20.))
+                            .show_ui(ui, |ui| {
+                                model::PromptKind::ALL.into_iter().for_each(|kind| {
+                                    if ui
+                                        .selectable_label(
+                                            self.selected_prompt_kind == kind,
+                                            egui::RichText::new(kind.label()).size(20.),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.select_prompt_kind(kind);
+                                    }
+                                });
+                            });
+                    });
+                    ui.label(
+                        egui::RichText::new(format!("Placeholders: {}", self.selected_prompt_kind.placeholders()))
+                            .weak(),
+                    );
+                    egui::TextEdit::multiline(&mut self.prompt_template_input)
+                        .id(egui::Id::new("prompt_template_editor"))
+                        .code_editor()
+                        .desired_rows(8)
+                        .desired_width(600.)
+                        .ui(ui);
+                    ui.horizontal(|ui| {
+                        if ui.button(egui::RichText::new("Save").size(20.)).clicked() {
+                            self.save_prompt_template();
+                        }
+                        if ui
+                            .add_enabled(
+                                self.template_validation.is_none(),
+                                egui::Button::new(egui::RichText::new("Validate").size(20.)),
+                            )
+                            .clicked()
+                        {
+                            self.validate_prompt_template();
+                        }
+                        if ui.button(egui::RichText::new("Reset to default").size(20.)).clicked() {
+                            self.reset_prompt_template();
+                        }
+                        if self.template_validation.is_some() {
+                            ui.spinner();
+                        }
+                    });
+                })
+            });
         });
+    }
+}
 
-        self.toasts.show(ctx);
+fn senses_list(ui: &mut egui::Ui, phrase: &model::Phrase) {
+    if phrase.senses.is_empty() && phrase.translation.is_empty() {
+        ui.label(
+            egui::RichText::new(format!("No {} translation saved yet", phrase.language))
+                .size(20.)
+                .weak(),
+        );
+    } else if phrase.senses.is_empty() {
+        ui.label(egui::RichText::new(phrase.translation.clone()).size(28.));
+    } else {
+        phrase.senses.iter().enumerate().for_each(|(index, sense)| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(egui::RichText::new(format!("{}. {}", index + 1, sense.translation)).size(28.));
+                if !sense.note.is_empty() {
+                    ui.label(egui::RichText::new(sense.note.clone()).size(18.).italics().weak());
+                }
+            });
+        });
+    }
+}
+
+fn senses_editor(ui: &mut egui::Ui, senses: &mut Vec<model::Sense>) {
+    let mut removed = None;
+    senses.iter_mut().enumerate().for_each(|(index, sense)| {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(format!("{}.", index + 1)).size(20.));
+            egui::TextEdit::singleline(&mut sense.translation)
+                .id(egui::Id::new(("sense_translation_editor", index)))
+                .hint_text("Translation")
+                .font(egui::FontId::new(20., egui::FontFamily::Proportional))
+                .ui(ui);
+            egui::TextEdit::singleline(&mut sense.note)
+                .id(egui::Id::new(("sense_note_editor", index)))
+                .hint_text("Usage note")
+                .font(egui::FontId::new(20., egui::FontFamily::Proportional))
+                .ui(ui);
+            if ui.button(egui::RichText::new("-").size(20.)).clicked() {
+                removed = Some(index);
+            }
+        });
+    });
+    if let Some(index) = removed {
+        senses.remove(index);
+    }
+    if ui.button(egui::RichText::new("+ Add sense").size(20.)).clicked() {
+        senses.push(model::Sense::default());
+    }
+}
+
+fn examples_list(ui: &mut egui::Ui, examples: &[model::Example]) {
+    examples.iter().for_each(|example| {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(
+                egui::RichText::new(example.hanzi.clone())
+                    .color(egui::Color32::YELLOW)
+                    .size(24.),
+            );
+            ui.label(egui::RichText::new(example.pinyin.clone()).size(18.));
+            ui.label(egui::RichText::new(example.translation.clone()).size(18.).weak());
+        });
+    });
+}
+
+fn optional_value<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<T>,
+    default: T,
+    range: std::ops::RangeInclusive<T>,
+    speed: f64,
+) {
+    let mut enabled = value.is_some();
+    if ui
+        .checkbox(&mut enabled, egui::RichText::new(label).size(20.))
+        .changed()
+    {
+        *value = if enabled { Some(default) } else { None };
+    }
+    if let Some(value) = value.as_mut() {
+        ui.add(egui::DragValue::new(value).range(range).speed(speed));
+    } else {
+        ui.label(egui::RichText::new("model default").weak());
+    }
+    ui.end_row();
+}
+
+fn character_buttons(ui: &mut egui::Ui, text: &str, size: f32, colors: &[egui::Color32]) -> Option<String> {
+    let mut clicked = None;
+    ui.horizontal_wrapped(|ui| {
+        text.chars()
+            .filter(|c| cjk::is_cjk(*c))
+            .enumerate()
+            .for_each(|(index, c)| {
+                let mut label = egui::RichText::new(c.to_string()).size(size);
+                if let Some(color) = colors.get(index) {
+                    label = label.color(*color);
+                }
+                if ui
+                    .add(egui::Button::new(label).frame(false))
+                    .on_hover_cursor(egui::CursorIcon::PointingHand)
+                    .clicked()
+                {
+                    clicked = Some(c.to_string());
+                }
+            });
+    });
+    clicked
+}
+
+fn tone_colors(tone_colors: &model::ToneColors, hanzi: &str, pinyin: &str) -> Vec<egui::Color32> {
+    if !tone_colors.enabled {
+        return Vec::new();
+    }
+    pinyin::tones(hanzi, pinyin)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|tone| tone_colors.color(tone))
+        .map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))
+        .collect()
+}
+
+fn hanzi_layout(text: &str, colors: &[egui::Color32], color: egui::Color32, size: f32) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let mut index = 0;
+    text.chars().for_each(|c| {
+        let color = if cjk::is_cjk(c) {
+            index += 1;
+            colors.get(index - 1).copied().unwrap_or(color)
+        } else {
+            color
+        };
+        job.append(
+            &c.to_string(),
+            0.,
+            egui::TextFormat::simple(egui::FontId::proportional(size), color),
+        );
+    });
+    job
+}
+
+fn ruby_line(
+    ui: &mut egui::Ui,
+    tokens: &[model::Ruby],
+    tone_colors: &model::ToneColors,
+    color: egui::Color32,
+    size: f32,
+) -> Option<String> {
+    let mut clicked = None;
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = size * 0.1;
+        tokens.iter().for_each(|token| {
+            let color = token
+                .reading
+                .as_ref()
+                .and_then(|(_, tone)| tone_colors.color(*tone))
+                .map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))
+                .unwrap_or(color);
+            let response = ruby_text(ui, token, color, size);
+            if token.reading.is_some() && response.on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
+                clicked = Some(token.text.clone());
+            }
+        });
+    });
+    clicked
+}
+
+fn ruby_text(ui: &mut egui::Ui, token: &model::Ruby, color: egui::Color32, size: f32) -> egui::Response {
+    let reading_size = size * 0.45;
+    let reading_height = reading_size * 1.3;
+    let text = ui
+        .painter()
+        .layout_no_wrap(token.text.clone(), egui::FontId::proportional(size), color);
+    let reading = token.reading.as_ref().map(|(reading, _)| {
+        ui.painter()
+            .layout_no_wrap(reading.clone(), egui::FontId::proportional(reading_size), color)
+    });
+    let width = reading
+        .as_ref()
+        .map(|reading| reading.size().x)
+        .unwrap_or_default()
+        .max(text.size().x);
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(width, reading_height + text.size().y), egui::Sense::click());
+    if ui.is_rect_visible(rect) {
+        let painter = ui.painter();
+        if let Some(reading) = reading {
+            let position = egui::pos2(rect.center().x - reading.size().x / 2., rect.top());
+            painter.galley(position, reading, color);
+        }
+        let position = egui::pos2(rect.center().x - text.size().x / 2., rect.top() + reading_height);
+        painter.galley(position, text, color);
+    }
+    response
+}
+
+fn pinyin_label(ui: &mut egui::Ui, text: &str, tone_colors: &model::ToneColors, size: f32) {
+    let color = ui.visuals().text_color();
+    let format = |color| egui::TextFormat::simple(egui::FontId::proportional(size), color);
+    let mut job = egui::text::LayoutJob::default();
+    let mut end = 0;
+    pinyin::syllables(text).into_iter().for_each(|syllable| {
+        job.append(&text[end..syllable.range.start], 0., format(color));
+        let syllable_color = tone_colors
+            .color(syllable.tone)
+            .map(|[r, g, b]| egui::Color32::from_rgb(r, g, b))
+            .unwrap_or(color);
+        job.append(&text[syllable.range.clone()], 0., format(syllable_color));
+        end = syllable.range.end;
+    });
+    job.append(&text[end..], 0., format(color));
+    ui.label(job);
+}
+
+fn tone_statistics(ui: &mut egui::Ui, statistics: &model::ToneStatistics) {
+    if statistics.tones.is_empty() {
+        ui.label(egui::RichText::new("No tones drilled yet").size(18.).weak());
+        return;
+    }
+    egui::Grid::new("tone_error_rates")
+        .num_columns(2)
+        .spacing([18., 8.])
+        .show(ui, |ui| {
+            statistics.tones.iter().for_each(|(tone, total, errors)| {
+                ui.label(
+                    egui::RichText::new(format!("{} tone: {} of {} wrong", tone.label(), errors, total)).size(18.),
+                );
+                ui.add(
+                    egui::ProgressBar::new(*errors as f32 / (*total).max(1) as f32)
+                        .desired_width(200.)
+                        .show_percentage(),
+                );
+                ui.end_row();
+            });
+        });
+    if !statistics.confused_pairs.is_empty() {
+        ui.label(egui::RichText::new("Most confused tone pairs").size(20.));
+        let number = |tone: Option<model::Tone>| tone.map(|tone| tone.number().to_string()).unwrap_or("–".to_string());
+        statistics.confused_pairs.iter().for_each(|pair| {
+            ui.label(
+                egui::RichText::new(format!(
+                    "{}-{} typed as {}-{}: {} times",
+                    pair.expected.0.number(),
+                    pair.expected.1.number(),
+                    number(pair.answered.0),
+                    number(pair.answered.1),
+                    pair.count
+                ))
+                .size(18.),
+            );
+        });
+    }
+}
+
+fn bar_chart(ui: &mut egui::Ui, id: &str, values: &[usize], first_day: i32, color: egui::Color32) {
+    let bars = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| egui_plot::Bar::new(f64::from(first_day + index as i32), *value as f64).width(0.8))
+        .collect();
+    let chart = egui_plot::BarChart::new(id, bars)
+        .color(color)
+        .element_formatter(Box::new(|bar, _| {
+            format!("{}: {}", day_label(bar.argument as i32), bar.value)
+        }));
+    egui_plot::Plot::new(id)
+        .width(360.)
+        .height(100.)
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .allow_double_click_reset(false)
+        .show_x(false)
+        .show_y(false)
+        .include_y(0.)
+        .x_axis_formatter(|mark, _| day_label(mark.value.round() as i32))
+        .show(ui, |plot_ui| plot_ui.bar_chart(chart));
+}
+
+fn day_label(day: i32) -> String {
+    match day {
+        0 => "today".to_string(),
+        -1 => "yesterday".to_string(),
+        1 => "tomorrow".to_string(),
+        day if day < 0 => format!("{} days ago", -day),
+        day => format!("in {} days", day),
+    }
+}
+
+fn speak_button(ui: &mut egui::Ui, busy: bool) -> bool {
+    if !tts::PLAYBACK {
+        return false;
+    }
+    ui.add_enabled(
+        !busy,
+        egui::Button::new(egui::RichText::new("🔊").size(20.)).frame(false),
+    )
+    .on_hover_text("Speak")
+    .clicked()
+}
+
+fn source_button(ui: &mut egui::Ui) -> bool {
+    ui.add(egui::Button::new(egui::RichText::new("📖").size(20.)).frame(false))
+        .on_hover_text("Open the source")
+        .clicked()
+}
+
+fn stroke_order(ui: &mut egui::Ui, strokes: &[model::Stroke], animation: &app::StrokeAnimation, size: f32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let to_screen = |point| to_screen(rect, point);
+    character_grid(&painter, rect);
+
+    let width = size * 0.06;
+    strokes.iter().enumerate().for_each(|(index, stroke)| {
+        painter.add(egui::Shape::closed_line(
+            stroke.outline.iter().copied().map(to_screen).collect(),
+            egui::Stroke::new(1., egui::Color32::GRAY),
+        ));
+        let median = stroke.median.iter().copied().map(to_screen).collect::<Vec<_>>();
+        if index < animation.step {
+            painter.add(egui::Shape::line(
+                median,
+                egui::Stroke::new(width, egui::Color32::WHITE),
+            ));
+        } else if index == animation.step && animation.progress > 0. {
+            painter.add(egui::Shape::line(
+                partial_line(&median, animation.progress),
+                egui::Stroke::new(width, egui::Color32::YELLOW),
+            ));
+        }
+    });
+}
+
+fn partial_line(points: &[egui::Pos2], fraction: f32) -> Vec<egui::Pos2> {
+    let total = points.windows(2).map(|pair| pair[0].distance(pair[1])).sum::<f32>();
+    let mut remaining = total * fraction.clamp(0., 1.);
+    let mut partial = points.iter().take(1).copied().collect::<Vec<_>>();
+    for pair in points.windows(2) {
+        let length = pair[0].distance(pair[1]);
+        if remaining >= length {
+            partial.push(pair[1]);
+            remaining -= length;
+        } else {
+            partial.push(pair[0].lerp(pair[1], remaining / length));
+            break;
+        }
+    }
+    partial
+}
+
+fn handwriting_canvas(ui: &mut egui::Ui, practice: &mut app::Practice, size: f32) -> bool {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::drag());
+    let painter = ui.painter_at(rect);
+    character_grid(&painter, rect);
+
+    if response.dragged()
+        && let Some(pos) = response.interact_pointer_pos()
+    {
+        practice.drawing.push(to_data(rect, pos));
+    }
+
+    let width = size * 0.06;
+    if let Some(character) = practice.character() {
+        character.strokes.iter().enumerate().for_each(|(index, stroke)| {
+            let median = stroke
+                .median
+                .iter()
+                .map(|point| to_screen(rect, *point))
+                .collect::<Vec<_>>();
+            if index < practice.step {
+                painter.add(egui::Shape::line(
+                    median.clone(),
+                    egui::Stroke::new(width, egui::Color32::WHITE),
+                ));
+            } else if practice.hint {
+                painter.add(egui::Shape::closed_line(
+                    stroke.outline.iter().map(|point| to_screen(rect, *point)).collect(),
+                    egui::Stroke::new(1., egui::Color32::DARK_GRAY),
+                ));
+            }
+            if index == practice.step
+                && practice
+                    .feedback
+                    .is_some_and(|feedback| feedback != model::StrokeFeedback::Correct)
+            {
+                painter.add(egui::Shape::line(median, egui::Stroke::new(2., egui::Color32::RED)));
+            }
+        });
+    }
+    painter.add(egui::Shape::line(
+        practice.drawing.iter().map(|point| to_screen(rect, *point)).collect(),
+        egui::Stroke::new(width * 0.5, egui::Color32::YELLOW),
+    ));
+
+    response.drag_stopped()
+}
+
+fn character_grid(painter: &egui::Painter, rect: egui::Rect) {
+    let guide = egui::Stroke::new(1., egui::Color32::from_gray(60));
+    painter.rect_stroke(rect, 0., guide, egui::StrokeKind::Inside);
+    painter.line_segment([rect.left_center(), rect.right_center()], guide);
+    painter.line_segment([rect.center_top(), rect.center_bottom()], guide);
+    painter.line_segment([rect.left_top(), rect.right_bottom()], guide);
+    painter.line_segment([rect.right_top(), rect.left_bottom()], guide);
+}
+
+fn to_screen(rect: egui::Rect, [x, y]: [f32; 2]) -> egui::Pos2 {
+    rect.min + egui::vec2(x / 1024. * rect.width(), (900. - y) / 1024. * rect.height())
+}
+
+fn to_data(rect: egui::Rect, pos: egui::Pos2) -> [f32; 2] {
+    [
+        (pos.x - rect.min.x) / rect.width() * 1024.,
+        900. - (pos.y - rect.min.y) / rect.height() * 1024.,
+    ]
+}
+
+fn other_script(ui: &mut egui::Ui, converter: &script::Converter, text: &str, preferred: model::Script, size: f32) {
+    let other = preferred.other();
+    let converted = converter.convert(text, other);
+    if converted != converter.convert(text, preferred) {
+        ui.label(
+            egui::RichText::new(format!("{}: {}", other.label(), converted))
+                .size(size)
+                .weak(),
+        );
+    }
+}
+
+fn hsk_badge(ui: &mut egui::Ui, version: model::HskVersion, level: Option<i32>) {
+    if let Some(level) = level {
+        egui::Frame::NONE
+            .fill(egui::Color32::DARK_BLUE)
+            .corner_radius(4.)
+            .inner_margin(egui::Margin::symmetric(6, 2))
+            .show(ui, |ui| {
+                ui.label(
+                    egui::RichText::new(format!("HSK{} {}", version.major(), level))
+                        .color(egui::Color32::WHITE)
+                        .size(14.),
+                );
+            });
     }
 }